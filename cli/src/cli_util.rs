@@ -20,31 +20,32 @@ use std::ffi::OsString;
 use std::fmt::Debug;
 use std::io::{self, Write as _};
 use std::path::{Path, PathBuf};
-use std::process::ExitCode;
+use std::process::{ExitCode, Stdio};
 use std::rc::Rc;
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::SystemTime;
-use std::{fs, str};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use std::{fs, iter, slice, str, thread};
 
 use clap::builder::{
     MapValueParser, NonEmptyStringValueParser, TypedValueParser, ValueParserFactory,
 };
-use clap::error::{ContextKind, ContextValue};
+use clap::error::{ContextKind, ContextValue, ErrorKind};
 use clap::{ArgAction, ArgMatches, Command, FromArgMatches};
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use jj_lib::backend::{ChangeId, CommitId, MergedTreeId, TreeValue};
 use jj_lib::commit::Commit;
+use jj_lib::dsl_util::{collect_similar, AliasDeclarationParser, AliasesMap};
 use jj_lib::fileset::FilesetExpression;
 use jj_lib::git_backend::GitBackend;
-use jj_lib::gitignore::{GitIgnoreError, GitIgnoreFile};
-use jj_lib::hex_util::to_reverse_hex;
+use jj_lib::gitignore::{GitIgnoreError, GitIgnoreFile, GitIgnoreMatch};
+use jj_lib::hex_util::{encode_reverse_hex, to_forward_hex};
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::matchers::Matcher;
 use jj_lib::merge::MergedTreeValue;
 use jj_lib::merged_tree::MergedTree;
-use jj_lib::object_id::ObjectId;
+use jj_lib::object_id::{HexPrefix, ObjectId, PrefixResolution};
 use jj_lib::op_store::{OpStoreError, OperationId, RefTarget, WorkspaceId};
 use jj_lib::op_walk::OpsetEvaluationError;
 use jj_lib::operation::Operation;
@@ -54,8 +55,8 @@ use jj_lib::repo::{
 };
 use jj_lib::repo_path::{RepoPath, RepoPathBuf, RepoPathUiConverter, UiPathParseError};
 use jj_lib::revset::{
-    RevsetAliasesMap, RevsetExpression, RevsetExtensions, RevsetFilterPredicate, RevsetFunction,
-    RevsetIteratorExt, RevsetModifier, RevsetParseContext, RevsetWorkspaceContext,
+    Revset, RevsetAliasesMap, RevsetExpression, RevsetExtensions, RevsetFilterPredicate,
+    RevsetFunction, RevsetIteratorExt, RevsetModifier, RevsetParseContext, RevsetWorkspaceContext,
     SymbolResolverExtension,
 };
 use jj_lib::rewrite::restore_tree;
@@ -63,7 +64,6 @@ use jj_lib::settings::{ConfigResultExt as _, UserSettings};
 use jj_lib::signing::SignInitError;
 use jj_lib::str_util::StringPattern;
 use jj_lib::transaction::Transaction;
-use jj_lib::view::View;
 use jj_lib::working_copy::{
     CheckoutStats, LockedWorkingCopy, SnapshotOptions, WorkingCopy, WorkingCopyFactory,
 };
@@ -73,14 +73,16 @@ use jj_lib::workspace::{
 };
 use jj_lib::{dag_walk, fileset, git, op_heads_store, op_walk, revset};
 use once_cell::unsync::OnceCell;
+use regex::{Captures, Regex};
 use tracing::instrument;
 use tracing_chrome::ChromeLayerBuilder;
 use tracing_subscriber::prelude::*;
 
 use crate::command_error::{
-    cli_error, config_error_with_message, handle_command_result, internal_error,
-    internal_error_with_message, user_error, user_error_with_hint, user_error_with_message,
-    CommandError,
+    cli_error, config_error_with_message, enable_backtrace_capture, format_similarity_hint,
+    handle_command_result, immutable_heads_config_error, internal_error,
+    internal_error_with_message, no_workspace_error, user_error, user_error_with_hint,
+    user_error_with_message, CommandError, CommandErrorKind,
 };
 use crate::commit_templater::{CommitTemplateLanguage, CommitTemplateLanguageExtension};
 use crate::config::{
@@ -90,15 +92,18 @@ use crate::config::{
 use crate::diff_util::{self, DiffFormat, DiffFormatArgs, DiffRenderer};
 use crate::formatter::{FormatRecorder, Formatter, PlainTextFormatter};
 use crate::git_util::{
-    is_colocated_git_workspace, print_failed_git_export, print_git_import_stats,
+    get_git_repo, is_colocated_git_workspace, print_failed_git_export, print_git_import_stats,
 };
 use crate::merge_tools::{DiffEditor, MergeEditor, MergeToolConfigError};
 use crate::operation_templater::OperationTemplateLanguageExtension;
-use crate::revset_util::RevsetExpressionEvaluator;
+use crate::revset_util::{
+    DivergentResolution, RevsetExpressionEvaluator, UserRevsetEvaluationError,
+};
+use crate::span_timing::{self, SpanTimingReport};
 use crate::template_builder::TemplateLanguage;
 use crate::template_parser::TemplateAliasesMap;
 use crate::templater::{PropertyPlaceholder, TemplateRenderer};
-use crate::ui::{ColorChoice, Ui};
+use crate::ui::{ColorChoice, StatusSink, Ui};
 use crate::{revset_util, template_builder, text_util};
 
 #[derive(Clone)]
@@ -122,6 +127,30 @@ pub struct TracingSubscription {
         tracing_subscriber::Registry,
     >,
     _chrome_tracing_flush_guard: ChromeTracingFlushGuard,
+    span_timing_report: Option<SpanTimingReport>,
+    init_warning: Option<String>,
+}
+
+/// Returns the directory to write chrome traces to, creating it if needed. If
+/// `JJ_TRACE_DIR` can't be created, falls back to the temporary directory and
+/// also returns a warning to be printed once the `Ui` is set up.
+fn chrome_trace_dir() -> (PathBuf, Option<String>) {
+    let Some(dir) = std::env::var_os("JJ_TRACE_DIR").filter(|dir| !dir.is_empty()) else {
+        return (PathBuf::new(), None);
+    };
+    let dir = PathBuf::from(dir);
+    match fs::create_dir_all(&dir) {
+        Ok(()) => (dir, None),
+        Err(err) => {
+            let temp_dir = std::env::temp_dir();
+            let warning = format!(
+                "Failed to create JJ_TRACE_DIR {}: {err}. Writing the trace to {} instead.",
+                dir.display(),
+                temp_dir.display()
+            );
+            (temp_dir, Some(warning))
+        }
+    }
 }
 
 impl TracingSubscription {
@@ -133,6 +162,7 @@ impl TracingSubscription {
             .from_env_lossy();
         let (filter, reload_log_filter) = tracing_subscriber::reload::Layer::new(filter);
 
+        let mut init_warning = None;
         let (chrome_tracing_layer, chrome_tracing_flush_guard) = match std::env::var("JJ_TRACE") {
             Ok(filename) => {
                 let filename = if filename.is_empty() {
@@ -146,9 +176,13 @@ impl TracingSubscription {
                 } else {
                     filename
                 };
+                // An absolute JJ_TRACE path wins over JJ_TRACE_DIR.
+                let (dir, dir_warning) = chrome_trace_dir();
+                init_warning = dir_warning;
+                let path = dir.join(filename);
                 let include_args = std::env::var("JJ_TRACE_INCLUDE_ARGS").is_ok();
                 let (layer, guard) = ChromeLayerBuilder::new()
-                    .file(filename)
+                    .file(path)
                     .include_args(include_args)
                     .build();
                 (
@@ -161,6 +195,13 @@ impl TracingSubscription {
             Err(_) => (None, ChromeTracingFlushGuard { _inner: None }),
         };
 
+        let (span_timing_layer, span_timing_report) = if std::env::var("JJ_TIME").is_ok() {
+            let (layer, report) = span_timing::new_layer();
+            (Some(layer), Some(report))
+        } else {
+            (None, None)
+        };
+
         tracing_subscriber::registry()
             .with(
                 tracing_subscriber::fmt::Layer::default()
@@ -168,11 +209,30 @@ impl TracingSubscription {
                     .with_filter(filter),
             )
             .with(chrome_tracing_layer)
+            .with(span_timing_layer)
             .init();
         TracingSubscription {
             reload_log_filter,
             _chrome_tracing_flush_guard: chrome_tracing_flush_guard,
+            span_timing_report,
+            init_warning,
+        }
+    }
+
+    /// Prints the problems found while initializing tracing, if any.
+    pub fn print_init_warnings(&self, ui: &Ui) -> io::Result<()> {
+        if let Some(warning) = &self.init_warning {
+            writeln!(ui.warning_default(), "{warning}")?;
+        }
+        Ok(())
+    }
+
+    /// Prints the time spent in each span if `JJ_TIME` is set.
+    pub fn print_span_timings(&self, ui: &Ui) -> io::Result<()> {
+        if let Some(report) = &self.span_timing_report {
+            report.write(&mut ui.stderr())?;
         }
+        Ok(())
     }
 
     pub fn enable_debug_logging(&self) -> Result<(), CommandError> {
@@ -202,9 +262,16 @@ pub struct CommandHelper {
     maybe_workspace_loader: Result<WorkspaceLoader, CommandError>,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
+    last_committed_op_id: LastCommittedOperation,
 }
 
 impl CommandHelper {
+    /// Records that the command committed the given operation. This is done
+    /// automatically for transactions finished by `WorkspaceCommandHelper`.
+    pub fn record_committed_operation(&self, op_id: &OperationId) {
+        self.last_committed_op_id.set(op_id);
+    }
+
     pub fn app(&self) -> &Command {
         &self.app
     }
@@ -229,10 +296,31 @@ impl CommandHelper {
         &self.global_args
     }
 
+    /// Prints "Nothing changed." for a command which had nothing to do.
+    ///
+    /// Returns an error if `--fail-on-no-change` was specified.
+    pub fn report_nothing_changed(&self, ui: &Ui) -> Result<(), CommandError> {
+        report_nothing_changed(ui, &self.global_args)
+    }
+
     pub fn settings(&self) -> &UserSettings {
         &self.settings
     }
 
+    /// Calls `f` with settings derived from the loaded config and the given
+    /// TOML layered on top of it.
+    ///
+    /// This is useful for custom commands which need to override some config
+    /// values. The settings of this `CommandHelper` aren't changed.
+    pub fn with_extra_config<R>(
+        &self,
+        toml_str: &str,
+        f: impl FnOnce(&UserSettings) -> R,
+    ) -> Result<R, CommandError> {
+        let config = self.layered_configs.merge_with_toml(toml_str)?;
+        Ok(f(&UserSettings::from_config(config)))
+    }
+
     pub fn resolved_config_values(
         &self,
         prefix: &ConfigNamePathBuf,
@@ -240,16 +328,39 @@ impl CommandHelper {
         self.layered_configs.resolved_config_values(prefix)
     }
 
+    /// Returns the path to the user config file. An empty file will be
+    /// created if it doesn't exist yet.
+    ///
+    /// The returned path can be passed to `write_config_value_to_file()`.
+    pub fn user_config_file_path(&self) -> Result<PathBuf, CommandError> {
+        // TODO(#531): Special-case for editors that can't handle viewing directories?
+        new_config_path()?.ok_or_else(|| user_error("No user config path found to edit"))
+    }
+
+    /// Returns the path to the config file of the current repo.
+    ///
+    /// The returned path can be passed to `write_config_value_to_file()`.
+    pub fn repo_config_file_path(&self) -> Result<PathBuf, CommandError> {
+        let repo_path = self.workspace_loader()?.repo_path();
+        Ok(self.layered_configs.repo_config_path(repo_path))
+    }
+
     pub fn revset_extensions(&self) -> &Arc<RevsetExtensions> {
         &self.revset_extensions
     }
 
-    /// Loads template aliases from the configs.
+    /// Loads template aliases from the configs and `--template-alias`.
     ///
     /// For most commands that depend on a loaded repo, you should use
     /// `WorkspaceCommandHelper::template_aliases_map()` instead.
     fn load_template_aliases(&self, ui: &Ui) -> Result<TemplateAliasesMap, CommandError> {
-        load_template_aliases(ui, &self.layered_configs)
+        let mut aliases_map = load_template_aliases(ui, &self.layered_configs)?;
+        insert_alias_args(
+            &mut aliases_map,
+            "--template-alias",
+            &self.global_args.template_alias,
+        )?;
+        Ok(aliases_map)
     }
 
     /// Parses template of the given language into evaluation tree.
@@ -284,13 +395,31 @@ impl CommandHelper {
     /// Loads workspace and repo, then snapshots the working copy if allowed.
     #[instrument(skip(self, ui))]
     pub fn workspace_helper(&self, ui: &mut Ui) -> Result<WorkspaceCommandHelper, CommandError> {
+        self.workspace_helper_with(ui, true)
+    }
+
+    /// Loads workspace and repo, then snapshots the working copy if
+    /// `snapshot` is true and snapshotting is allowed.
+    ///
+    /// This is useful for custom commands which decide at runtime whether
+    /// the working copy should be snapshotted.
+    #[instrument(skip(self, ui))]
+    pub fn workspace_helper_with(
+        &self,
+        ui: &mut Ui,
+        snapshot: bool,
+    ) -> Result<WorkspaceCommandHelper, CommandError> {
         let mut workspace_command = self.workspace_helper_no_snapshot(ui)?;
-        workspace_command.maybe_snapshot(ui)?;
+        if snapshot {
+            workspace_command.maybe_snapshot(ui)?;
+        }
         Ok(workspace_command)
     }
 
     /// Loads workspace and repo, but never snapshots the working copy. Most
     /// commands should use `workspace_helper()` instead.
+    ///
+    /// Equivalent to `workspace_helper_with(ui, false)`.
     #[instrument(skip(self, ui))]
     pub fn workspace_helper_no_snapshot(
         &self,
@@ -309,8 +438,7 @@ impl CommandHelper {
         let factory: Result<_, WorkspaceLoadError> = loader
             .get_working_copy_factory(&self.working_copy_factories)
             .map_err(|e| e.into());
-        let factory = factory
-            .map_err(|err| map_workspace_load_error(err, self.global_args.repository.as_deref()))?;
+        let factory = factory.map_err(|err| self.map_workspace_load_error(err))?;
         Ok(factory)
     }
 
@@ -323,7 +451,29 @@ impl CommandHelper {
                 &self.store_factories,
                 &self.working_copy_factories,
             )
-            .map_err(|err| map_workspace_load_error(err, self.global_args.repository.as_deref()))
+            .map_err(|err| self.map_workspace_load_error(err))
+    }
+
+    /// Like `map_workspace_load_error()`, but also lists the registered
+    /// working-copy types if the workspace uses an unknown one.
+    fn map_workspace_load_error(&self, err: WorkspaceLoadError) -> CommandError {
+        match err {
+            WorkspaceLoadError::StoreLoadError(
+                err @ StoreLoadError::UnsupportedType {
+                    store: "working copy",
+                    ..
+                },
+            ) => {
+                let names = self.working_copy_factories.keys().sorted().join(", ");
+                user_error_with_message(
+                    "This version of the jj binary doesn't support this type of working copy",
+                    err,
+                )
+                .hinted(format!("Supported working copy types: {names}"))
+                .hinted(unsupported_store_type_hint("workspace"))
+            }
+            err => map_workspace_load_error(err, self.global_args.repository.as_deref()),
+        }
     }
 
     #[instrument(skip_all)]
@@ -333,22 +483,68 @@ impl CommandHelper {
         repo_loader: &RepoLoader,
     ) -> Result<Operation, CommandError> {
         if self.global_args.at_operation == "@" {
+            let auto_resolve = self
+                .settings
+                .config()
+                .get_bool("operation.auto-resolve-concurrent")?;
+            let report_resolution = self
+                .settings
+                .config()
+                .get_bool("ui.report-concurrent-resolution")?;
+            let report_args = self
+                .settings
+                .config()
+                .get_bool("ui.report-concurrent-args")?;
+            let merge_order = self
+                .settings
+                .config()
+                .get::<OperationMergeOrder>("operation.merge-order")
+                .map_err(|err| config_error_with_message("Invalid `operation.merge-order`", err))?;
+            let record_display_args = self
+                .settings
+                .config()
+                .get_bool("operation.record-display-args")?;
             op_heads_store::resolve_op_heads(
                 repo_loader.op_heads_store().as_ref(),
                 repo_loader.op_store(),
-                |op_heads| {
-                    writeln!(
-                        ui.status(),
-                        "Concurrent modification detected, resolving automatically.",
-                    )?;
+                |mut op_heads| {
+                    if !auto_resolve {
+                        return Err(concurrent_operations_error(&op_heads));
+                    }
+                    match merge_order {
+                        // Already sorted by timestamp, then by id.
+                        OperationMergeOrder::Timestamp => {}
+                        OperationMergeOrder::Id => op_heads.sort_by(|a, b| a.id().cmp(b.id())),
+                    }
+                    if report_resolution {
+                        writeln!(
+                            ui.status(),
+                            "Concurrent modification detected, resolving automatically.",
+                        )?;
+                        if report_args {
+                            for op in &op_heads {
+                                let metadata = op.metadata();
+                                let args =
+                                    metadata.tags.get("args").unwrap_or(&metadata.description);
+                                writeln!(
+                                    ui.status(),
+                                    "  {} {args}",
+                                    short_operation_hash(op.id())
+                                )?;
+                            }
+                        }
+                    }
                     let base_repo = repo_loader.load_at(&op_heads[0])?;
-                    // TODO: It may be helpful to print each operation we're merging here
-                    let mut tx =
-                        start_repo_transaction(&base_repo, &self.settings, &self.string_args);
+                    let mut tx = start_repo_transaction(
+                        &base_repo,
+                        &self.settings,
+                        &self.string_args,
+                        record_display_args,
+                    );
                     for other_op_head in op_heads.into_iter().skip(1) {
                         tx.merge_operation(other_op_head)?;
                         let num_rebased = tx.mut_repo().rebase_descendants(&self.settings)?;
-                        if num_rebased > 0 {
+                        if num_rebased > 0 && report_resolution {
                             writeln!(
                                 ui.status(),
                                 "Rebased {num_rebased} descendant commits onto commits rewritten \
@@ -483,12 +679,17 @@ pub struct WorkspaceCommandHelper {
     revset_extensions: Arc<RevsetExtensions>,
     // TODO: Parsed template can be cached if it doesn't capture 'repo lifetime
     commit_summary_template_text: String,
+    working_copy_updated_template_text: Option<String>,
     commit_template_extensions: Vec<Arc<dyn CommitTemplateLanguageExtension>>,
     revset_aliases_map: RevsetAliasesMap,
     template_aliases_map: TemplateAliasesMap,
     may_update_working_copy: bool,
     working_copy_shared_with_git: bool,
+    auto_git_export: bool,
     path_converter: RepoPathUiConverter,
+    path_style: PathStyle,
+    record_display_args: bool,
+    last_committed_op_id: LastCommittedOperation,
 }
 
 impl WorkspaceCommandHelper {
@@ -502,15 +703,38 @@ impl WorkspaceCommandHelper {
         let settings = command.settings.clone();
         let commit_summary_template_text =
             settings.config().get_string("templates.commit_summary")?;
-        let revset_aliases_map = revset_util::load_revset_aliases(ui, &command.layered_configs)?;
+        let working_copy_updated_template_text = settings
+            .config()
+            .get_string("templates.working_copy_updated")
+            .optional()?;
+        let mut revset_aliases_map =
+            revset_util::load_revset_aliases(ui, &command.layered_configs)?;
+        insert_alias_args(
+            &mut revset_aliases_map,
+            "--revset-alias",
+            &command.global_args.revset_alias,
+        )?;
         let template_aliases_map = command.load_template_aliases(ui)?;
         let loaded_at_head = command.global_args.at_operation == "@";
         let may_update_working_copy = loaded_at_head && !command.global_args.ignore_working_copy;
         let working_copy_shared_with_git = is_colocated_git_workspace(&workspace, &repo);
+        let auto_git_export = !command.global_args.no_git_export
+            && settings
+                .config()
+                .get_bool("git.auto-export")
+                .optional()?
+                .unwrap_or(true);
         let path_converter = RepoPathUiConverter::Fs {
             cwd: command.cwd.clone(),
             base: workspace.workspace_root().clone(),
         };
+        let path_style = settings
+            .config()
+            .get::<PathStyle>("ui.path-style")
+            .map_err(|err| config_error_with_message("Invalid `ui.path-style`", err))?;
+        let record_display_args = settings
+            .config()
+            .get_bool("operation.record-display-args")?;
         let helper = Self {
             string_args: command.string_args.clone(),
             global_args: command.global_args.clone(),
@@ -519,16 +743,24 @@ impl WorkspaceCommandHelper {
             user_repo: ReadonlyUserRepo::new(repo),
             revset_extensions: command.revset_extensions.clone(),
             commit_summary_template_text,
+            working_copy_updated_template_text,
             commit_template_extensions: command.commit_template_extensions.clone(),
             revset_aliases_map,
             template_aliases_map,
             may_update_working_copy,
             working_copy_shared_with_git,
+            auto_git_export,
             path_converter,
+            path_style,
+            record_display_args,
+            last_committed_op_id: command.last_committed_op_id.clone(),
         };
         // Parse commit_summary template (and short-prefixes revset) early to
         // report error before starting mutable operation.
         helper.parse_commit_template(&helper.commit_summary_template_text)?;
+        if let Some(text) = &helper.working_copy_updated_template_text {
+            helper.parse_commit_template(text)?;
+        }
         Ok(helper)
     }
 
@@ -536,12 +768,20 @@ impl WorkspaceCommandHelper {
         self.user_repo.git_backend()
     }
 
+    /// Opens the underlying Git repository, or returns a user error if the
+    /// repo isn't backed by Git.
+    pub fn git_repo(&self) -> Result<git2::Repository, CommandError> {
+        get_git_repo(self.repo().store())
+    }
+
     pub fn check_working_copy_writable(&self) -> Result<(), CommandError> {
         if self.may_update_working_copy {
             Ok(())
         } else {
             let hint = if self.global_args.ignore_working_copy {
                 "Don't use --ignore-working-copy."
+            } else if self.global_args.allow_stale_working_copy {
+                "Run `jj workspace update-stale` first, or don't use --allow-stale-working-copy."
             } else {
                 "Don't use --at-op."
             };
@@ -601,7 +841,18 @@ impl WorkspaceCommandHelper {
         let mut tx = tx.into_inner();
         let old_git_head = self.repo().view().git_head().clone();
         let new_git_head = tx.mut_repo().view().git_head().clone();
-        if let Some(new_git_head_id) = new_git_head.as_normal() {
+        let wc_parent_ids = if let Some(wc_commit) = self.wc_commit()? {
+            wc_commit.parent_ids().to_vec()
+        } else {
+            vec![]
+        };
+        // HEAD may point to the working-copy parent even if has_changes() is
+        // true, e.g. if it got detached at the same commit. There's no need to
+        // abandon the working-copy commit in that case.
+        if let Some(new_git_head_id) = new_git_head
+            .as_normal()
+            .filter(|&id| wc_parent_ids != slice::from_ref(id))
+        {
             let workspace_id = self.workspace_id().to_owned();
             let new_git_head_commit = tx.mut_repo().store().get_commit(new_git_head_id)?;
             tx.mut_repo()
@@ -613,6 +864,7 @@ impl WorkspaceCommandHelper {
             locked_ws.locked_wc().reset(&new_git_head_commit)?;
             tx.mut_repo().rebase_descendants(&self.settings)?;
             self.user_repo = ReadonlyUserRepo::new(tx.commit("import git head"));
+            self.last_committed_op_id.set(self.user_repo.repo.op_id());
             locked_ws.finish(self.user_repo.repo.op_id().clone())?;
             if old_git_head.is_present() {
                 writeln!(
@@ -623,7 +875,8 @@ impl WorkspaceCommandHelper {
                 // Don't print verbose message on initial checkout.
             }
         } else {
-            // Unlikely, but the HEAD ref got deleted by git?
+            // HEAD is unchanged relative to the working-copy parent, or
+            // (unlikely) the HEAD ref got deleted by git.
             self.finish_transaction(ui, tx, "import git head")?;
         }
         Ok(())
@@ -640,10 +893,26 @@ impl WorkspaceCommandHelper {
     #[instrument(skip_all)]
     fn import_git_refs(&mut self, ui: &mut Ui) -> Result<(), CommandError> {
         let git_settings = self.settings.git_settings();
+        let ref_patterns = auto_import_ref_patterns(self.settings.config())?;
+        let remotes = self
+            .settings
+            .config()
+            .get::<Vec<String>>("git.auto-import-remotes")
+            .optional()?;
         let mut tx = self.start_transaction();
         // Automated import shouldn't fail because of reserved remote name.
         let stats = git::import_some_refs(tx.mut_repo(), &git_settings, |ref_name| {
             !git::is_reserved_git_remote_ref(ref_name)
+                && ref_patterns.as_ref().map_or(true, |patterns| {
+                    git::to_git_ref_name(ref_name)
+                        .is_some_and(|name| patterns.iter().any(|pattern| pattern.matches(&name)))
+                })
+                && match (ref_name, &remotes) {
+                    (git::RefName::RemoteBranch { remote, .. }, Some(remotes)) => {
+                        remotes.contains(remote)
+                    }
+                    _ => true,
+                }
         })?;
         if !tx.mut_repo().has_changes() {
             return Ok(());
@@ -667,6 +936,10 @@ impl WorkspaceCommandHelper {
         Ok(())
     }
 
+    pub fn settings(&self) -> &UserSettings {
+        &self.settings
+    }
+
     pub fn repo(&self) -> &Arc<ReadonlyRepo> {
         &self.user_repo.repo
     }
@@ -675,13 +948,18 @@ impl WorkspaceCommandHelper {
         self.workspace.working_copy()
     }
 
+    /// Loads the operation the working copy was last updated to. It differs
+    /// from the operation of `repo()` if the working copy is stale, or if the
+    /// repo was loaded at another operation.
+    pub fn working_copy_operation(&self) -> Result<Operation, CommandError> {
+        Ok(read_working_copy_operation(&self.workspace)?)
+    }
+
     pub fn unchecked_start_working_copy_mutation(
         &mut self,
     ) -> Result<(LockedWorkspace, Commit), CommandError> {
         self.check_working_copy_writable()?;
-        let wc_commit = if let Some(wc_commit_id) = self.get_wc_commit_id() {
-            self.repo().store().get_commit(wc_commit_id)?
-        } else {
+        let Some(wc_commit) = self.wc_commit()? else {
             return Err(user_error("Nothing checked out in this workspace"));
         };
 
@@ -693,6 +971,26 @@ impl WorkspaceCommandHelper {
     pub fn start_working_copy_mutation(
         &mut self,
     ) -> Result<(LockedWorkspace, Commit), CommandError> {
+        if let Some(lock_timeout) = self.working_copy_lock_timeout()? {
+            // Another process may be in the middle of updating the working
+            // copy. Wait for it to settle down before giving up. The lock is
+            // released between attempts, and acquired again below.
+            let deadline = Instant::now() + lock_timeout;
+            let mut interval = Duration::from_millis(1);
+            loop {
+                let is_fresh = {
+                    let (mut locked_ws, wc_commit) =
+                        self.unchecked_start_working_copy_mutation()?;
+                    wc_commit.tree_id() == locked_ws.locked_wc().old_tree_id()
+                };
+                let now = Instant::now();
+                if is_fresh || now >= deadline {
+                    break;
+                }
+                thread::sleep(interval.min(deadline - now));
+                interval = (interval * 2).min(Duration::from_millis(500));
+            }
+        }
         let (mut locked_ws, wc_commit) = self.unchecked_start_working_copy_mutation()?;
         if wc_commit.tree_id() != locked_ws.locked_wc().old_tree_id() {
             return Err(user_error("Concurrent working copy operation. Try again."));
@@ -700,6 +998,18 @@ impl WorkspaceCommandHelper {
         Ok((locked_ws, wc_commit))
     }
 
+    /// How long to keep retrying if the working copy was concurrently
+    /// modified. `None` means the mutation should fail immediately.
+    fn working_copy_lock_timeout(&self) -> Result<Option<Duration>, CommandError> {
+        let timeout_ms = self
+            .settings
+            .config()
+            .get::<u64>("working-copy.lock-timeout")
+            .optional()
+            .map_err(|err| config_error_with_message("Invalid `working-copy.lock-timeout`", err))?;
+        Ok(timeout_ms.map(Duration::from_millis))
+    }
+
     pub fn workspace_root(&self) -> &PathBuf {
         self.workspace.workspace_root()
     }
@@ -712,12 +1022,93 @@ impl WorkspaceCommandHelper {
         self.repo().view().get_wc_commit_id(self.workspace_id())
     }
 
+    /// Returns the working-copy commit of this workspace, or `None` if nothing
+    /// is checked out.
+    pub fn wc_commit(&self) -> Result<Option<Commit>, CommandError> {
+        let commit = self
+            .get_wc_commit_id()
+            .map(|id| self.repo().store().get_commit(id))
+            .transpose()?;
+        Ok(commit)
+    }
+
+    /// Returns the parents of the working-copy commit of this workspace, or an
+    /// empty list if nothing is checked out.
+    pub fn wc_parent_commits(&self) -> Result<Vec<Commit>, CommandError> {
+        let Some(wc_commit) = self.wc_commit()? else {
+            return Ok(vec![]);
+        };
+        let parents = wc_commit.parents().try_collect()?;
+        Ok(parents)
+    }
+
+    /// Snapshots the working copy and fails if the working-copy commit isn't
+    /// clean as defined by `working-copy.clean`. Commands that may lose
+    /// uncommitted changes can call this before doing anything.
+    pub fn require_clean_working_copy(&mut self, ui: &mut Ui) -> Result<(), CommandError> {
+        self.maybe_snapshot(ui)?;
+        let Some(wc_commit) = self.wc_commit()? else {
+            return Ok(());
+        };
+        let definition = self
+            .settings
+            .config()
+            .get::<CleanWorkingCopy>("working-copy.clean")
+            .map_err(|err| config_error_with_message("Invalid `working-copy.clean`", err))?;
+        let is_clean = match definition {
+            CleanWorkingCopy::EmptyDiff => wc_commit.is_empty(self.repo().as_ref())?,
+            CleanWorkingCopy::EmptyCommit => wc_commit.is_discardable(self.repo().as_ref())?,
+        };
+        if is_clean {
+            return Ok(());
+        }
+        Err(user_error_with_hint(
+            format!(
+                "The working copy {} is not clean",
+                short_commit_hash(wc_commit.id())
+            ),
+            "Run `jj new` to start a new change on top of it first.",
+        ))
+    }
+
     pub fn working_copy_shared_with_git(&self) -> bool {
         self.working_copy_shared_with_git
     }
 
+    /// Returns the remote branches which are not tracked by the local branches
+    /// of the same name, sorted by branch and remote name.
+    pub fn untracked_remote_branches(&self) -> Vec<RemoteBranchName> {
+        self.remote_branches_by_tracking_state(false)
+    }
+
+    /// Returns the remote branches which are tracked by the local branches of
+    /// the same name, sorted by branch and remote name.
+    pub fn tracked_remote_branches(&self) -> Vec<RemoteBranchName> {
+        self.remote_branches_by_tracking_state(true)
+    }
+
+    fn remote_branches_by_tracking_state(&self, is_tracking: bool) -> Vec<RemoteBranchName> {
+        let mut names = self
+            .repo()
+            .view()
+            .all_remote_branches()
+            .filter(|(_, remote_ref)| remote_ref.is_tracking() == is_tracking)
+            .map(|((branch, remote), _)| RemoteBranchName {
+                branch: branch.to_owned(),
+                remote: remote.to_owned(),
+            })
+            .collect_vec();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Formats a path relative to the current directory, with the separators
+    /// specified by `ui.path-style`.
     pub fn format_file_path(&self, file: &RepoPath) -> String {
-        self.path_converter.format_file_path(file)
+        let path = self.path_converter.format_file_path(file);
+        self.path_style
+            .convert_separators(path, std::path::MAIN_SEPARATOR)
     }
 
     /// Parses a path relative to cwd into a RepoPath, which is relative to the
@@ -803,6 +1194,31 @@ impl WorkspaceCommandHelper {
         Ok(git_ignores)
     }
 
+    /// Returns the `.gitignore` line which decides whether the `path` in the
+    /// working copy is ignored, or `None` if no pattern applies. The global
+    /// excludes and the `.gitignore` files in the ancestor directories of the
+    /// `path` are taken into account.
+    pub fn explain_ignored(
+        &self,
+        path: &RepoPath,
+    ) -> Result<Option<GitIgnoreMatch>, GitIgnoreError> {
+        let workspace_root = self.workspace_root();
+        let mut dirs = iter::successors(path.parent(), |dir| dir.parent()).collect_vec();
+        dirs.reverse();
+        let mut git_ignores = self.base_ignores()?;
+        for dir in dirs {
+            git_ignores = git_ignores.chain_with_file(
+                &dir.to_internal_dir_string(),
+                dir.to_fs_path(workspace_root).join(".gitignore"),
+            )?;
+        }
+        let mut path_str = path.as_internal_file_string().to_owned();
+        if path.to_fs_path(workspace_root).is_dir() {
+            path_str.push('/');
+        }
+        Ok(git_ignores.matched(&path_str))
+    }
+
     /// Creates textual diff renderer of the specified `formats`.
     pub fn diff_renderer(&self, formats: Vec<DiffFormat>) -> DiffRenderer<'_> {
         DiffRenderer::new(self.repo().as_ref(), &self.path_converter, formats)
@@ -883,7 +1299,35 @@ impl WorkspaceCommandHelper {
     /// Resolve a revset to a single revision. Return an error if the revset is
     /// empty or has multiple revisions.
     pub fn resolve_single_rev(&self, revision_arg: &RevisionArg) -> Result<Commit, CommandError> {
+        if let Some(commit) = self.resolve_id_prefix_fast(revision_arg.as_ref())? {
+            return Ok(commit);
+        }
         let expression = self.parse_revset(revision_arg)?;
+        // Two commits are enough to tell whether the revset is a single
+        // revision, so don't evaluate further unless we need to report an error.
+        let mut commits = self.evaluate_revset_limited(expression.expression().clone(), 2)?;
+        if commits.len() == 1 {
+            return Ok(commits.pop().unwrap());
+        }
+        if commits.len() > 1
+            && revset_util::divergent_resolution_setting(self.settings.config())?
+                == DivergentResolution::Latest
+        {
+            // Divergent commits made by concurrent operations can be indexed
+            // in any order, so compare their timestamps.
+            let candidates: Vec<Commit> = expression.evaluate_to_commits()?.try_collect()?;
+            let change_id = candidates[0].change_id();
+            if candidates
+                .iter()
+                .all(|commit| commit.change_id() == change_id)
+            {
+                let latest = candidates
+                    .iter()
+                    .max_by_key(|commit| (commit.committer().timestamp.timestamp, commit.id()))
+                    .unwrap();
+                return Ok(latest.clone());
+            }
+        }
         let should_hint_about_all_prefix = false;
         revset_util::evaluate_revset_to_single_commit(
             revision_arg.as_ref(),
@@ -893,6 +1337,89 @@ impl WorkspaceCommandHelper {
         )
     }
 
+    /// Resolve a revset to a single revision's commit id without loading the
+    /// commit. Return an error if the revset is empty or has multiple
+    /// revisions.
+    pub fn resolve_single_commit_id(
+        &self,
+        revision_arg: &RevisionArg,
+    ) -> Result<CommitId, CommandError> {
+        let expression = self.parse_revset(revision_arg)?;
+        let mut iter = expression.evaluate_to_commit_ids()?.fuse();
+        if let (Some(commit_id), None) = (iter.next(), iter.next()) {
+            return Ok(commit_id);
+        }
+        // Load the commits to report the error in the same way as
+        // resolve_single_rev().
+        let should_hint_about_all_prefix = false;
+        let commit = revset_util::evaluate_revset_to_single_commit(
+            revision_arg.as_ref(),
+            &expression,
+            || self.commit_summary_template(),
+            should_hint_about_all_prefix,
+        )?;
+        Ok(commit.id().clone())
+    }
+
+    /// Evaluates a revset to commit ids without loading the commits. The ids
+    /// are produced lazily in reverse topological order, so the caller can
+    /// process them as they come.
+    pub fn resolve_revset_commit_ids(
+        &self,
+        revision_arg: &RevisionArg,
+    ) -> Result<impl Iterator<Item = CommitId> + '_, CommandError> {
+        let expression = self.parse_revset(revision_arg)?;
+        Ok(expression.evaluate_to_commit_ids()?)
+    }
+
+    /// Resolves a bare commit or change id prefix without evaluating a revset.
+    ///
+    /// Returns `None` if the symbol isn't an unambiguous id prefix, or if it
+    /// could also refer to an alias, tag, branch, or git ref. The caller
+    /// should then fall back to the revset engine, which reports errors such
+    /// as ambiguous prefixes.
+    fn resolve_id_prefix_fast(&self, symbol: &str) -> Result<Option<Commit>, CommandError> {
+        if symbol.is_empty()
+            || !symbol
+                .bytes()
+                .all(|b| b.is_ascii_digit() || b.is_ascii_lowercase())
+        {
+            return Ok(None);
+        }
+        let repo = self.repo().as_ref();
+        let view = repo.view();
+        if self.revset_aliases_map.get_symbol(symbol).is_some()
+            || view.get_tag(symbol).is_present()
+            || view.get_local_branch(symbol).is_present()
+            || view.get_git_ref(symbol).is_present()
+            || view.get_git_ref(&format!("refs/{symbol}")).is_present()
+        {
+            return Ok(None);
+        }
+        let id_prefix_context = self.id_prefix_context()?;
+        let commit_prefix_resolution = match HexPrefix::new(symbol) {
+            Some(prefix) => id_prefix_context.resolve_commit_prefix(repo, &prefix),
+            None => PrefixResolution::NoMatch,
+        };
+        let commit_id = match commit_prefix_resolution {
+            PrefixResolution::SingleMatch(id) => id,
+            PrefixResolution::AmbiguousMatch => return Ok(None),
+            PrefixResolution::NoMatch => {
+                let Some(prefix) = to_forward_hex(symbol).as_deref().and_then(HexPrefix::new)
+                else {
+                    return Ok(None);
+                };
+                match id_prefix_context.resolve_change_prefix(repo, &prefix) {
+                    PrefixResolution::SingleMatch(ids) if ids.len() == 1 => {
+                        ids.into_iter().next().unwrap()
+                    }
+                    _ => return Ok(None),
+                }
+            }
+        };
+        Ok(Some(repo.store().get_commit(&commit_id)?))
+    }
+
     /// Evaluates revset expressions to non-empty set of commits. The returned
     /// set preserves the order of the input expressions.
     ///
@@ -901,16 +1428,30 @@ impl WorkspaceCommandHelper {
     pub fn resolve_some_revsets_default_single(
         &self,
         revision_args: &[RevisionArg],
+    ) -> Result<IndexSet<Commit>, CommandError> {
+        self.resolve_some_revsets(revision_args, false)
+    }
+
+    /// Like `resolve_some_revsets_default_single()`, but if `all` is true,
+    /// every input expression may be evaluated to any number of revisions as
+    /// if it were prefixed with `all:`. Commands should pass their `--all`
+    /// flag here.
+    pub fn resolve_some_revsets(
+        &self,
+        revision_args: &[RevisionArg],
+        all: bool,
     ) -> Result<IndexSet<Commit>, CommandError> {
         let mut all_commits = IndexSet::new();
         for revision_arg in revision_args {
             let (expression, modifier) = self.parse_revset_with_modifier(revision_arg)?;
             let all = match modifier {
                 Some(RevsetModifier::All) => true,
-                None => self
-                    .settings
-                    .config()
-                    .get_bool("ui.always-allow-large-revsets")?,
+                None => {
+                    all || self
+                        .settings
+                        .config()
+                        .get_bool("ui.always-allow-large-revsets")?
+                }
             };
             if all {
                 for commit in expression.evaluate_to_commits()? {
@@ -950,6 +1491,21 @@ impl WorkspaceCommandHelper {
         Ok(expression)
     }
 
+    /// Parses the given revset expression as if `at_commit` were the
+    /// working-copy commit, i.e. `@` resolves to `at_commit`.
+    pub fn parse_revset_with_at(
+        &self,
+        revision_arg: &RevisionArg,
+        at_commit: &CommitId,
+    ) -> Result<RevsetExpressionEvaluator<'_>, CommandError> {
+        let context = self.revset_parse_context();
+        let (expression, None | Some(RevsetModifier::All)) =
+            revset::parse_with_modifier(revision_arg.as_ref(), &context)?;
+        let expression =
+            revset::substitute_working_copy(expression, self.workspace_id(), at_commit);
+        self.attach_revset_evaluator(expression)
+    }
+
     fn parse_revset_with_modifier(
         &self,
         revision_arg: &RevisionArg,
@@ -986,6 +1542,22 @@ impl WorkspaceCommandHelper {
         ))
     }
 
+    /// Evaluates the revset `expression` to at most `limit` commits, in
+    /// reverse topological order. The evaluation stops as soon as `limit`
+    /// commits are found.
+    pub fn evaluate_revset_limited(
+        &self,
+        expression: Rc<RevsetExpression>,
+        limit: usize,
+    ) -> Result<Vec<Commit>, CommandError> {
+        let commits = self
+            .attach_revset_evaluator(expression)?
+            .evaluate_to_commits()?
+            .take(limit)
+            .try_collect()?;
+        Ok(commits)
+    }
+
     pub(crate) fn revset_parse_context(&self) -> RevsetParseContext {
         let workspace_context = RevsetWorkspaceContext {
             path_converter: &self.path_converter,
@@ -999,14 +1571,19 @@ impl WorkspaceCommandHelper {
         )
     }
 
+    /// Returns the revset expression within which short commit/change id
+    /// prefixes are disambiguated. Defaults to the default log revset.
+    pub fn short_prefixes_revset_string(&self) -> String {
+        self.settings
+            .config()
+            .get_string("revsets.short-prefixes")
+            .unwrap_or_else(|_| self.settings.default_revset())
+    }
+
     pub fn id_prefix_context(&self) -> Result<&IdPrefixContext, CommandError> {
         self.user_repo.id_prefix_context.get_or_try_init(|| {
             let mut context: IdPrefixContext = IdPrefixContext::new(self.revset_extensions.clone());
-            let revset_string: String = self
-                .settings
-                .config()
-                .get_string("revsets.short-prefixes")
-                .unwrap_or_else(|_| self.settings.default_revset());
+            let revset_string = self.short_prefixes_revset_string();
             if !revset_string.is_empty() {
                 let (expression, modifier) =
                     revset::parse_with_modifier(&revset_string, &self.revset_parse_context())
@@ -1056,6 +1633,16 @@ impl WorkspaceCommandHelper {
         )
     }
 
+    /// Reads commit template from the given file, and parses it into
+    /// evaluation tree.
+    pub fn parse_commit_template_from_path(
+        &self,
+        path: &Path,
+    ) -> Result<TemplateRenderer<'_, Commit>, CommandError> {
+        let template_text = read_template_file(path)?;
+        self.parse_commit_template(&template_text)
+    }
+
     /// Creates commit template language environment for this workspace.
     pub fn commit_template_language(&self) -> Result<CommitTemplateLanguage<'_>, CommandError> {
         Ok(CommitTemplateLanguage::new(
@@ -1120,9 +1707,7 @@ impl WorkspaceCommandHelper {
         let to_rewrite_revset =
             RevsetExpression::commits(commits.into_iter().cloned().collect_vec());
         let immutable = revset_util::parse_immutable_expression(&self.revset_parse_context())
-            .map_err(|e| {
-                config_error_with_message("Invalid `revset-aliases.immutable_heads()`", e)
-            })?;
+            .map_err(immutable_heads_config_error)?;
         let mut expression = RevsetExpressionEvaluator::new(
             repo,
             self.revset_extensions.clone(),
@@ -1131,9 +1716,9 @@ impl WorkspaceCommandHelper {
         );
         expression.intersect_with(&to_rewrite_revset);
 
-        let mut commit_id_iter = expression.evaluate_to_commit_ids().map_err(|e| {
-            config_error_with_message("Invalid `revset-aliases.immutable_heads()`", e)
-        })?;
+        let mut commit_id_iter = expression
+            .evaluate_to_commit_ids()
+            .map_err(immutable_heads_config_error)?;
 
         if let Some(commit_id) = commit_id_iter.next() {
             let error = if &commit_id == self.repo().store().root_commit_id() {
@@ -1194,15 +1779,40 @@ impl WorkspaceCommandHelper {
                     };
                     (repo, wc_commit)
                 }
+                Ok(WorkingCopyFreshness::WorkingCopyStale)
+                    if self.global_args.allow_stale_working_copy =>
+                {
+                    drop(locked_ws);
+                    writeln!(
+                        ui.warning_default(),
+                        "The working copy is stale (not updated since operation {}). Loading \
+                         the repo at that operation.",
+                        short_operation_hash(&old_op_id)
+                    )?;
+                    let wc_operation = read_working_copy_operation(&self.workspace)?;
+                    self.user_repo = ReadonlyUserRepo::new(repo.reload_at(&wc_operation)?);
+                    // Like --ignore-working-copy, the stale working copy is neither
+                    // snapshotted nor updated.
+                    self.may_update_working_copy = false;
+                    return Ok(());
+                }
                 Ok(WorkingCopyFreshness::WorkingCopyStale) => {
                     return Err(user_error_with_hint(
-                        format!(
-                            "The working copy is stale (not updated since operation {}).",
-                            short_operation_hash(&old_op_id)
-                        ),
-                        "Run `jj workspace update-stale` to update it.
+                        format_message(
+                            &self.settings,
+                            "stale-working-copy",
+                            "The working copy is stale (not updated since operation \
+                             {operation}).",
+                            &[("operation", &short_operation_hash(&old_op_id))],
+                        )?,
+                        format_message(
+                            &self.settings,
+                            "stale-working-copy-hint",
+                            "Run `jj workspace update-stale` to update it.
 See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-working-copy \
-                         for more information.",
+                             for more information.",
+                            &[],
+                        )?,
                     ));
                 }
                 Ok(WorkingCopyFreshness::SiblingOperation) => {
@@ -1216,9 +1826,14 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
                 Err(OpStoreError::ObjectNotFound { .. }) => {
                     return Err(user_error_with_hint(
                         "Could not read working copy's operation.",
-                        "Run `jj workspace update-stale` to recover.
+                        format_message(
+                            &self.settings,
+                            "stale-working-copy-recover-hint",
+                            "Run `jj workspace update-stale` to recover.
 See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-working-copy \
-                         for more information.",
+                             for more information.",
+                            &[],
+                        )?,
                     ))
                 }
                 Err(e) => return Err(e.into()),
@@ -1230,11 +1845,16 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
             fsmonitor_settings: self.settings.fsmonitor_settings()?,
             progress: progress.as_ref().map(|x| x as _),
             max_new_file_size: self.settings.max_new_file_size()?,
+            parallelism: self.settings.snapshot_parallelism()?,
         })?;
         drop(progress);
         if new_tree_id != *wc_commit.tree_id() {
-            let mut tx =
-                start_repo_transaction(&self.user_repo.repo, &self.settings, &self.string_args);
+            let mut tx = start_repo_transaction(
+                &self.user_repo.repo,
+                &self.settings,
+                &self.string_args,
+                self.record_display_args,
+            );
             tx.set_is_snapshot(true);
             let mut_repo = tx.mut_repo();
             let commit = mut_repo
@@ -1245,54 +1865,59 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
 
             // Rebase descendants
             let num_rebased = mut_repo.rebase_descendants(&self.settings)?;
-            if num_rebased > 0 {
+            if num_rebased > 0 && num_rebased >= rebase_report_threshold(&self.settings)? {
                 writeln!(
                     ui.status(),
                     "Rebased {num_rebased} descendant commits onto updated working copy"
                 )?;
             }
 
-            if self.working_copy_shared_with_git {
+            if self.working_copy_shared_with_git && self.auto_git_export {
                 let failed_branches = git::export_refs(mut_repo)?;
                 print_failed_git_export(ui, &failed_branches)?;
             }
 
             self.user_repo = ReadonlyUserRepo::new(tx.commit("snapshot working copy"));
+            self.last_committed_op_id.set(self.user_repo.repo.op_id());
         }
         locked_ws.finish(self.user_repo.repo.op_id().clone())?;
         Ok(())
     }
 
-    fn update_working_copy(
+    /// Updates the working copy from `maybe_old_commit` to `new_commit`.
+    ///
+    /// Nothing is printed. Returns the stats of the checkout, or `None` if the
+    /// tree didn't change. The caller may report the stats with
+    /// `print_checkout_stats()` or format them itself.
+    pub fn update_working_copy(
         &mut self,
-        ui: &mut Ui,
         maybe_old_commit: Option<&Commit>,
         new_commit: &Commit,
-    ) -> Result<(), CommandError> {
+    ) -> Result<Option<CheckoutStats>, CommandError> {
         assert!(self.may_update_working_copy);
-        let stats = update_working_copy(
+        update_working_copy(
             &self.user_repo.repo,
             &mut self.workspace,
             maybe_old_commit,
             new_commit,
-        )?;
+        )
+    }
+
+    /// Reports the working-copy update done by `update_working_copy()`.
+    fn report_working_copy_update(
+        &self,
+        ui: &mut Ui,
+        maybe_old_commit: Option<&Commit>,
+        new_commit: &Commit,
+        stats: Option<CheckoutStats>,
+    ) -> Result<(), CommandError> {
         if Some(new_commit) != maybe_old_commit {
             if let Some(mut formatter) = ui.status_formatter() {
-                let template = self.commit_summary_template();
-                write!(formatter, "Working copy now at: ")?;
-                formatter.with_label("working_copy", |fmt| template.format(new_commit, fmt))?;
-                writeln!(formatter)?;
-                for parent in new_commit.parents() {
-                    let parent = parent?;
-                    //                "Working copy now at: "
-                    write!(formatter, "Parent commit      : ")?;
-                    template.format(&parent, formatter.as_mut())?;
-                    writeln!(formatter)?;
-                }
+                self.write_working_copy_updated(formatter.as_mut(), new_commit)?;
             }
         }
         if let Some(stats) = stats {
-            print_checkout_stats(ui, stats, new_commit)?;
+            print_checkout_stats(ui, self, stats, new_commit)?;
         }
         if Some(new_commit) != maybe_old_commit {
             if let Some(mut formatter) = ui.status_formatter() {
@@ -1306,9 +1931,48 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
         Ok(())
     }
 
+    /// Writes the new working-copy commit and its parents after the working
+    /// copy got updated, using `templates.working_copy_updated` if set.
+    fn write_working_copy_updated(
+        &self,
+        formatter: &mut dyn Formatter,
+        new_commit: &Commit,
+    ) -> Result<(), CommandError> {
+        if let Some(text) = &self.working_copy_updated_template_text {
+            let template = self
+                .parse_commit_template(text)
+                .expect("parse error should be confined by WorkspaceCommandHelper::new()");
+            template.format(new_commit, formatter)?;
+            writeln!(formatter)?;
+            return Ok(());
+        }
+        let template = self.commit_summary_template();
+        write!(formatter, "Working copy now at: ")?;
+        formatter.with_label("working_copy", |fmt| template.format(new_commit, fmt))?;
+        writeln!(formatter)?;
+        for parent in new_commit.parents() {
+            let parent = parent?;
+            //                "Working copy now at: "
+            write!(formatter, "Parent commit      : ")?;
+            template.format(&parent, formatter)?;
+            writeln!(formatter)?;
+        }
+        Ok(())
+    }
+
     pub fn start_transaction(&mut self) -> WorkspaceCommandTransaction {
-        let tx = start_repo_transaction(self.repo(), &self.settings, &self.string_args);
-        WorkspaceCommandTransaction { helper: self, tx }
+        let tx = start_repo_transaction(
+            self.repo(),
+            &self.settings,
+            &self.string_args,
+            self.record_display_args,
+        );
+        let id_prefix_context = IdPrefixContext::new(self.revset_extensions.clone());
+        WorkspaceCommandTransaction {
+            helper: self,
+            tx,
+            id_prefix_context,
+        }
     }
 
     fn finish_transaction(
@@ -1318,11 +1982,10 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
         description: impl Into<String>,
     ) -> Result<(), CommandError> {
         if !tx.mut_repo().has_changes() {
-            writeln!(ui.status(), "Nothing changed.")?;
-            return Ok(());
+            return report_nothing_changed(ui, &self.global_args);
         }
         let num_rebased = tx.mut_repo().rebase_descendants(&self.settings)?;
-        if num_rebased > 0 {
+        if num_rebased > 0 && num_rebased >= rebase_report_threshold(&self.settings)? {
             writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
         }
 
@@ -1361,25 +2024,44 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
             .transpose()?;
 
         if self.working_copy_shared_with_git {
-            let git_repo = self.git_backend().unwrap().open_git_repo()?;
+            let git_repo = self.git_repo()?;
             if let Some(wc_commit) = &maybe_new_wc_commit {
                 git::reset_head(tx.mut_repo(), &git_repo, wc_commit)?;
             }
-            let failed_branches = git::export_refs(tx.mut_repo())?;
-            print_failed_git_export(ui, &failed_branches)?;
+            if self.auto_git_export {
+                let failed_branches = git::export_refs(tx.mut_repo())?;
+                print_failed_git_export(ui, &failed_branches)?;
+            }
         }
 
         self.user_repo = ReadonlyUserRepo::new(tx.commit(description));
+        self.last_committed_op_id.set(self.user_repo.repo.op_id());
+        if self.global_args.debug_op_ids {
+            writeln!(ui.stderr(), "Base operation: {}", old_repo.op_id().hex())?;
+            writeln!(ui.stderr(), "New operation: {}", self.repo().op_id().hex())?;
+        }
         self.report_repo_changes(ui, &old_repo)?;
 
         if self.may_update_working_copy {
             if let Some(new_commit) = &maybe_new_wc_commit {
-                self.update_working_copy(ui, maybe_old_wc_commit.as_ref(), new_commit)?;
+                let stats = self.update_working_copy(maybe_old_wc_commit.as_ref(), new_commit)?;
+                self.report_working_copy_update(
+                    ui,
+                    maybe_old_wc_commit.as_ref(),
+                    new_commit,
+                    stats,
+                )?;
             } else {
                 // It seems the workspace was deleted, so we shouldn't try to
                 // update it.
             }
         }
+        run_post_operation_hook(
+            ui,
+            &self.settings,
+            self.workspace_root(),
+            self.repo().op_id(),
+        )?;
 
         let settings = &self.settings;
         if settings.user_name().is_empty() || settings.user_email().is_empty() {
@@ -1447,31 +2129,35 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
 
         // TODO: Also report new divergence and maybe resolved divergence
         let template = self.commit_summary_template();
+        let limit = self
+            .settings
+            .config()
+            .get::<usize>("ui.max-conflict-report")
+            .optional()
+            .map_err(|err| config_error_with_message("Invalid `ui.max-conflict-report`", err))?
+            .unwrap_or(usize::MAX);
         if !resolved_conflicts_by_change_id.is_empty() {
             writeln!(
                 fmt,
                 "Existing conflicts were resolved or abandoned from these commits:"
             )?;
-            for (_, old_commits) in &resolved_conflicts_by_change_id {
-                // TODO: Report which ones were resolved and which ones were abandoned. However,
-                // that involves resolving the change_id among the visible commits in the new
-                // repo, which isn't currently supported by Google's revset engine.
-                for commit in old_commits {
-                    write!(fmt, "  ")?;
-                    template.format(commit, fmt.as_mut())?;
-                    writeln!(fmt)?;
-                }
-            }
+            // TODO: Report which ones were resolved and which ones were abandoned. However,
+            // that involves resolving the change_id among the visible commits in the new
+            // repo, which isn't currently supported by Google's revset engine.
+            let old_commits = resolved_conflicts_by_change_id
+                .values()
+                .flatten()
+                .collect_vec();
+            write_truncated_list(fmt.as_mut(), &old_commits, limit, |fmt, commit| {
+                template.format(commit, fmt)
+            })?;
         }
         if !new_conflicts_by_change_id.is_empty() {
             writeln!(fmt, "New conflicts appeared in these commits:")?;
-            for (_, new_commits) in &new_conflicts_by_change_id {
-                for commit in new_commits {
-                    write!(fmt, "  ")?;
-                    template.format(commit, fmt.as_mut())?;
-                    writeln!(fmt)?;
-                }
-            }
+            let new_commits = new_conflicts_by_change_id.values().flatten().collect_vec();
+            write_truncated_list(fmt.as_mut(), &new_commits, limit, |fmt, commit| {
+                template.format(commit, fmt)
+            })?;
         }
 
         // Hint that the user might want to `jj new` to the first conflict commit to
@@ -1501,6 +2187,24 @@ See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-workin
             )?;
         }
 
+        if self
+            .settings
+            .config()
+            .get_bool("ui.report-total-conflicts")?
+        {
+            let num_conflicts = new_heads
+                .ancestors()
+                .intersection(&conflicts)
+                .evaluate_programmatic(new_repo)?
+                .iter()
+                .count();
+            if num_conflicts == 1 {
+                writeln!(fmt, "You have 1 conflicted commit.")?;
+            } else {
+                writeln!(fmt, "You have {num_conflicts} conflicted commits.")?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1599,6 +2303,8 @@ Then run `jj squash` to move the resolution into the conflicted commit."#,
 pub struct WorkspaceCommandTransaction<'a> {
     helper: &'a mut WorkspaceCommandHelper,
     tx: Transaction,
+    // TODO: Use the disambiguation revset
+    id_prefix_context: IdPrefixContext,
 }
 
 impl WorkspaceCommandTransaction<'_> {
@@ -1642,24 +2348,53 @@ impl WorkspaceCommandTransaction<'_> {
         formatter: &mut dyn Formatter,
         commit: &Commit,
     ) -> std::io::Result<()> {
-        // TODO: Use the disambiguation revset
-        let id_prefix_context = IdPrefixContext::new(self.helper.revset_extensions.clone());
+        self.commit_summary_template().format(commit, formatter)
+    }
+
+    /// Template for one-line summary of a commit within the transaction.
+    ///
+    /// Use this instead of `write_commit_summary()` if you have many commits
+    /// to process, so the template is parsed only once.
+    pub fn commit_summary_template(&self) -> TemplateRenderer<'_, Commit> {
         let language = CommitTemplateLanguage::new(
             self.tx.repo(),
             self.helper.workspace_id(),
             self.helper.revset_parse_context(),
-            &id_prefix_context,
+            &self.id_prefix_context,
             &self.helper.commit_template_extensions,
         );
-        let template = self
-            .helper
+        self.helper
             .parse_template(
                 &language,
                 &self.helper.commit_summary_template_text,
                 CommitTemplateLanguage::wrap_commit,
             )
-            .expect("parse error should be confined by WorkspaceCommandHelper::new()");
-        template.format(commit, formatter)
+            .expect("parse error should be confined by WorkspaceCommandHelper::new()")
+    }
+
+    /// Attaches the revset `expression` to be evaluated against the
+    /// transaction's repo, which includes the changes made so far.
+    ///
+    /// Symbols are resolved in the same way as in the base workspace helper.
+    /// Short commit/change id prefixes are disambiguated within all commits.
+    pub fn attach_revset_evaluator(
+        &self,
+        expression: Rc<RevsetExpression>,
+    ) -> RevsetExpressionEvaluator<'_> {
+        RevsetExpressionEvaluator::new(
+            self.tx.repo(),
+            self.helper.revset_extensions.clone(),
+            &self.id_prefix_context,
+            expression,
+        )
+    }
+
+    /// Evaluates the revset `expression` against the transaction's repo.
+    pub fn evaluate_revset(
+        &self,
+        expression: Rc<RevsetExpression>,
+    ) -> Result<Box<dyn Revset + '_>, UserRevsetEvaluationError> {
+        self.attach_revset_evaluator(expression).evaluate()
     }
 
     pub fn finish(self, ui: &mut Ui, description: impl Into<String>) -> Result<(), CommandError> {
@@ -1692,12 +2427,154 @@ impl WorkspaceCommandTransaction<'_> {
     }
 }
 
-fn find_workspace_dir(cwd: &Path) -> &Path {
-    cwd.ancestors()
-        .find(|path| path.join(".jj").is_dir())
+/// Finds the closest ancestor of `cwd` containing a `.jj` directory or file.
+/// The search doesn't go up into any of the `ceiling_dirs`, but `cwd` itself is
+/// always searched.
+fn find_workspace_dir<'a>(cwd: &'a Path, ceiling_dirs: &[PathBuf]) -> &'a Path {
+    let parents = cwd
+        .ancestors()
+        .skip(1)
+        .take_while(|path| !ceiling_dirs.iter().any(|dir| dir == path));
+    iter::once(cwd)
+        .chain(parents)
+        .find(|path| {
+            // .jj may also be a file pointing to the actual directory.
+            let jj_path = path.join(".jj");
+            jj_path.is_dir() || jj_path.is_file()
+        })
         .unwrap_or(cwd)
 }
 
+/// Expands a leading `~` and `$VAR` or `${VAR}` references in the
+/// `-R/--repository` path, in case the shell didn't. References to unset
+/// variables are kept as is.
+fn expand_repository_path(path: &str) -> PathBuf {
+    let var_regex = Regex::new(r"\$(?:\{(\w+)\}|(\w+))").unwrap();
+    let path = var_regex.replace_all(path, |caps: &Captures| {
+        let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        env::var(name).unwrap_or_else(|_| caps[0].to_owned())
+    });
+    let home_relative = match path.strip_prefix('~') {
+        Some("") => Some(""),
+        Some(rest) if rest.starts_with(std::path::is_separator) => Some(&rest[1..]),
+        _ => None,
+    };
+    match (home_relative, dirs::home_dir()) {
+        (Some(rest), Some(home_dir)) => home_dir.join(rest),
+        _ => PathBuf::from(path.as_ref()),
+    }
+}
+
+/// Loads the directories which the workspace search shouldn't go up into from
+/// `workspace.search-ceiling` and `$JJ_CEILING_DIRECTORIES`. Relative paths
+/// are ignored.
+fn workspace_search_ceiling(config: &config::Config) -> Result<Vec<PathBuf>, CommandError> {
+    let mut dirs: Vec<PathBuf> = config
+        .get::<Vec<String>>("workspace.search-ceiling")
+        .optional()
+        .map_err(|err| config_error_with_message("Invalid `workspace.search-ceiling`", err))?
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    if let Some(value) = env::var_os("JJ_CEILING_DIRECTORIES") {
+        dirs.extend(env::split_paths(&value));
+    }
+    let dirs = dirs
+        .into_iter()
+        .filter(|dir| dir.is_absolute())
+        .map(|dir| dir.canonicalize().unwrap_or(dir))
+        .collect();
+    Ok(dirs)
+}
+
+/// Parses `git.auto-import-ref-patterns`, which limits the Git refs to be
+/// imported automatically. Returns `None` if all refs should be imported.
+fn auto_import_ref_patterns(
+    config: &config::Config,
+) -> Result<Option<Vec<StringPattern>>, CommandError> {
+    let Some(patterns) = config
+        .get::<Vec<String>>("git.auto-import-ref-patterns")
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let patterns = patterns
+        .iter()
+        .map(|s| {
+            StringPattern::glob(s).map_err(|e| {
+                config_error_with_message(
+                    format!("Error parsing '{s}' for git.auto-import-ref-patterns"),
+                    e,
+                )
+            })
+        })
+        .try_collect()?;
+    Ok(Some(patterns))
+}
+
+fn concurrent_operations_error(op_heads: &[Operation]) -> CommandError {
+    let op_summaries = op_heads
+        .iter()
+        .map(|op| {
+            format!(
+                "  {} {}",
+                short_operation_hash(op.id()),
+                op.metadata().description
+            )
+        })
+        .join("\n");
+    user_error_with_hint(
+        "Concurrent modification detected, and `operation.auto-resolve-concurrent` is disabled",
+        format!(
+            "The divergent operations are:
+{op_summaries}
+Run `jj op log` to inspect them, and merge them by running a command with \
+             `--config-toml operation.auto-resolve-concurrent=true`."
+        ),
+    )
+}
+
+/// Minimum number of rebased descendants worth reporting after a command.
+fn rebase_report_threshold(settings: &UserSettings) -> Result<usize, CommandError> {
+    settings
+        .config()
+        .get::<usize>("ui.rebase-report-threshold")
+        .map_err(|err| config_error_with_message("Invalid `ui.rebase-report-threshold`", err))
+}
+
+/// Looks up the user-facing message `key` in the `messages` config table,
+/// falling back to the built-in English `default`.
+///
+/// `{name}` placeholders in the message are substituted with the matching
+/// `args`.
+pub fn format_message(
+    settings: &UserSettings,
+    key: &str,
+    default: &str,
+    args: &[(&str, &dyn fmt::Display)],
+) -> Result<String, CommandError> {
+    let config_key = format!("messages.{key}");
+    let template = settings
+        .config()
+        .get_string(&config_key)
+        .optional()
+        .map_err(|err| config_error_with_message(format!("Invalid `{config_key}`"), err))?;
+    let mut message = template.unwrap_or_else(|| default.to_owned());
+    for (name, value) in args {
+        message = message.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    Ok(message)
+}
+
+/// Hint for a store type which this build of jj doesn't support, found in the
+/// given kind of directory ("repo" or "workspace").
+fn unsupported_store_type_hint(dir_kind: &str) -> String {
+    format!(
+        "The {dir_kind} may have been created by a newer or custom build of jj. Try upgrading jj."
+    )
+}
+
 fn map_workspace_load_error(err: WorkspaceLoadError, workspace_path: Option<&str>) -> CommandError {
     match err {
         WorkspaceLoadError::NoWorkspaceHere(wc_path) => {
@@ -1705,15 +2582,15 @@ fn map_workspace_load_error(err: WorkspaceLoadError, workspace_path: Option<&str
             let workspace_path_str = workspace_path.unwrap_or(".");
             let message = format!(r#"There is no jj repo in "{workspace_path_str}""#);
             let git_dir = wc_path.join(".git");
+            let err = no_workspace_error(message);
             if git_dir.is_dir() {
-                user_error_with_hint(
-                    message,
+                err.hinted(
                     "It looks like this is a git repo. You can create a jj repo backed by it by \
                      running this:
 jj git init --colocate",
                 )
             } else {
-                user_error(message)
+                err
             }
         }
         WorkspaceLoadError::RepoDoesNotExist(repo_dir) => user_error(format!(
@@ -1721,10 +2598,11 @@ jj git init --colocate",
             repo_dir.display(),
         )),
         WorkspaceLoadError::StoreLoadError(err @ StoreLoadError::UnsupportedType { .. }) => {
-            internal_error_with_message(
+            user_error_with_message(
                 "This version of the jj binary doesn't support this type of repo",
                 err,
             )
+            .hinted(unsupported_store_type_hint("repo"))
         }
         WorkspaceLoadError::StoreLoadError(
             err @ (StoreLoadError::ReadError { .. } | StoreLoadError::Backend(_)),
@@ -1742,6 +2620,7 @@ pub fn start_repo_transaction(
     repo: &Arc<ReadonlyRepo>,
     settings: &UserSettings,
     string_args: &[String],
+    record_display_args: bool,
 ) -> Transaction {
     let mut tx = repo.start_transaction(settings);
     // TODO: Either do better shell-escaping here or store the values in some list
@@ -1769,9 +2648,56 @@ pub fn start_repo_transaction(
     let mut quoted_strings = vec!["jj".to_string()];
     quoted_strings.extend(string_args.iter().skip(1).map(shell_escape));
     tx.set_tag("args".to_string(), quoted_strings.join(" "));
+    if record_display_args {
+        let mut display_strings = vec!["jj"];
+        display_strings.extend(string_args.iter().skip(1).map(String::as_str));
+        tx.set_tag("args_display".to_string(), display_strings.join(" "));
+    }
     tx
 }
 
+/// Separators used in file paths printed by `format_file_path()`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub enum PathStyle {
+    /// The platform's separator, e.g. `\` on Windows.
+    #[default]
+    Native,
+    /// Always `/`.
+    Slash,
+}
+
+impl PathStyle {
+    /// Converts a path formatted with the given native `separator`.
+    fn convert_separators(self, path: String, separator: char) -> String {
+        match self {
+            PathStyle::Native => path,
+            PathStyle::Slash => path.replace(separator, "/"),
+        }
+    }
+}
+
+/// Order in which concurrent operations are merged.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub enum OperationMergeOrder {
+    /// Oldest operation first, with ties broken by operation id.
+    #[default]
+    Timestamp,
+    /// By operation id, regardless of when the operations were created.
+    Id,
+}
+
+/// Which working-copy commits `require_clean_working_copy()` accepts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub enum CleanWorkingCopy {
+    /// The working-copy commit has no changes compared to its parents.
+    EmptyDiff,
+    /// The working-copy commit has no changes and no description.
+    EmptyCommit,
+}
+
 /// Whether the working copy is stale or not.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum WorkingCopyFreshness {
@@ -1786,6 +2712,14 @@ pub enum WorkingCopyFreshness {
     SiblingOperation,
 }
 
+/// Loads the operation the working copy of `workspace` was last updated to.
+pub fn read_working_copy_operation(workspace: &Workspace) -> Result<Operation, OpStoreError> {
+    let op_store = workspace.repo_loader().op_store();
+    let op_id = workspace.working_copy().operation_id();
+    let op_data = op_store.read_operation(op_id)?;
+    Ok(Operation::new(op_store.clone(), op_id.clone(), op_data))
+}
+
 #[instrument(skip_all)]
 pub fn check_stale_working_copy(
     locked_wc: &dyn LockedWorkingCopy,
@@ -1920,34 +2854,61 @@ pub fn print_conflicted_paths(
     Ok(())
 }
 
+/// Reads template text from the given file.
+pub fn read_template_file(path: &Path) -> Result<String, CommandError> {
+    fs::read_to_string(path).map_err(|err| {
+        user_error_with_message(
+            format!("Failed to read template file {}", path.display()),
+            err,
+        )
+    })
+}
+
 pub fn print_checkout_stats(
     ui: &mut Ui,
+    workspace_command: &WorkspaceCommandHelper,
     stats: CheckoutStats,
     new_commit: &Commit,
-) -> Result<(), std::io::Error> {
+) -> Result<(), CommandError> {
+    let settings = workspace_command.settings();
     if stats.added_files > 0 || stats.updated_files > 0 || stats.removed_files > 0 {
-        writeln!(
-            ui.status(),
-            "Added {} files, modified {} files, removed {} files",
-            stats.added_files,
-            stats.updated_files,
-            stats.removed_files
+        let message = format_message(
+            settings,
+            "checkout-stats",
+            "Added {added} files, modified {modified} files, removed {removed} files",
+            &[
+                ("added", &stats.added_files),
+                ("modified", &stats.updated_files),
+                ("removed", &stats.removed_files),
+            ],
         )?;
+        writeln!(ui.status(), "{message}")?;
     }
     if stats.skipped_files != 0 {
-        writeln!(
-            ui.warning_default(),
-            "{} of those updates were skipped because there were conflicting changes in the \
-             working copy.",
-            stats.skipped_files
+        let message = format_message(
+            settings,
+            "checkout-skipped",
+            "{skipped} of those updates were skipped because there were conflicting changes \
+             in the working copy.",
+            &[("skipped", &stats.skipped_files)],
         )?;
-        writeln!(
-            ui.hint_default(),
-            "Inspect the changes compared to the intended target with `jj diff --from {}`.
-Discard the conflicting changes with `jj restore --from {}`.",
-            short_commit_hash(new_commit.id()),
-            short_commit_hash(new_commit.id())
+        writeln!(ui.warning_default(), "{message}")?;
+        // Don't flood the terminal if e.g. a whole directory was skipped.
+        const MAX_SKIPPED_PATHS_TO_SHOW: usize = 10;
+        write_truncated_list(
+            ui.stderr_formatter().as_mut(),
+            &stats.skipped_paths,
+            MAX_SKIPPED_PATHS_TO_SHOW,
+            |formatter, path| write!(formatter, "{}", workspace_command.format_file_path(path)),
         )?;
+        let hint = format_message(
+            settings,
+            "checkout-skipped-hint",
+            "Inspect the changes compared to the intended target with `jj diff --from {commit}`.
+Discard the conflicting changes with `jj restore --from {commit}`.",
+            &[("commit", &short_commit_hash(new_commit.id()))],
+        )?;
+        writeln!(ui.hint_default(), "{hint}")?;
     }
     Ok(())
 }
@@ -1979,17 +2940,46 @@ pub fn print_unmatched_explicit_paths<'a>(
     Ok(())
 }
 
-pub fn print_trackable_remote_branches(ui: &Ui, view: &View) -> io::Result<()> {
-    let remote_branch_names = view
-        .branches()
-        .filter(|(_, branch_target)| branch_target.local_target.is_present())
-        .flat_map(|(name, branch_target)| {
-            branch_target
-                .remote_refs
-                .into_iter()
-                .filter(|&(_, remote_ref)| !remote_ref.is_tracking())
-                .map(move |(remote, _)| format!("{name}@{remote}"))
-        })
+fn report_nothing_changed(ui: &Ui, global_args: &GlobalArgs) -> Result<(), CommandError> {
+    writeln!(ui.status(), "Nothing changed.")?;
+    if global_args.fail_on_no_change {
+        return Err(CommandError::new(
+            CommandErrorKind::NothingChanged,
+            "Nothing changed",
+        ));
+    }
+    Ok(())
+}
+
+/// Writes the `items` on separate indented lines. If there are more than
+/// `limit` items, only the first `limit` ones are written, followed by
+/// "... and N more".
+pub fn write_truncated_list<T>(
+    formatter: &mut dyn Formatter,
+    items: &[T],
+    limit: usize,
+    mut write_item: impl FnMut(&mut dyn Formatter, &T) -> io::Result<()>,
+) -> io::Result<()> {
+    for item in items.iter().take(limit) {
+        write!(formatter, "  ")?;
+        write_item(formatter, item)?;
+        writeln!(formatter)?;
+    }
+    if items.len() > limit {
+        writeln!(formatter, "  ... and {} more", items.len() - limit)?;
+    }
+    Ok(())
+}
+
+pub fn print_trackable_remote_branches(
+    ui: &Ui,
+    workspace_command: &WorkspaceCommandHelper,
+) -> Result<(), CommandError> {
+    let view = workspace_command.repo().view();
+    let remote_branch_names = workspace_command
+        .untracked_remote_branches()
+        .into_iter()
+        .filter(|name| view.get_local_branch(&name.branch).is_present())
         .collect_vec();
     if remote_branch_names.is_empty() {
         return Ok(());
@@ -2000,15 +2990,38 @@ pub fn print_trackable_remote_branches(ui: &Ui, view: &View) -> io::Result<()> {
             formatter.labeled("hint").with_heading("Hint: "),
             "The following remote branches aren't associated with the existing local branches:"
         )?;
-        for full_name in &remote_branch_names {
-            write!(formatter, "  ")?;
-            writeln!(formatter.labeled("branch"), "{full_name}")?;
-        }
-        writeln!(
-            formatter.labeled("hint").with_heading("Hint: "),
-            "Run `jj branch track {names}` to keep local branches updated on future pulls.",
-            names = remote_branch_names.join(" "),
+        let limit = workspace_command
+            .settings
+            .config()
+            .get::<usize>("ui.hint-list-limit")
+            .map_err(|err| config_error_with_message("Invalid `ui.hint-list-limit`", err))?;
+        write_truncated_list(
+            formatter.as_mut(),
+            &remote_branch_names,
+            limit,
+            |formatter, name| write!(formatter.labeled("branch"), "{name}"),
         )?;
+        if remote_branch_names.len() <= limit {
+            writeln!(
+                formatter.labeled("hint").with_heading("Hint: "),
+                "Run `jj branch track {names}` to keep local branches updated on future pulls.",
+                names = remote_branch_names.iter().join(" "),
+            )?;
+        } else {
+            // Suggest patterns rather than listing all the names
+            let patterns = remote_branch_names
+                .iter()
+                .map(|name| &name.remote)
+                .sorted()
+                .dedup()
+                .map(|remote| format!("'glob:*@{remote}'"))
+                .join(" ");
+            writeln!(
+                formatter.labeled("hint").with_heading("Hint: "),
+                "Run `jj branch track {patterns}` to keep all branches of the remote updated on \
+                 future pulls.",
+            )?;
+        }
     }
     Ok(())
 }
@@ -2071,6 +3084,33 @@ fn load_template_aliases(
     Ok(aliases_map)
 }
 
+/// Adds the one-off aliases specified as `NAME=DEFINITION` by `flag` (e.g.
+/// `--revset-alias`). They take precedence over the aliases loaded from config.
+fn insert_alias_args<P>(
+    aliases_map: &mut AliasesMap<P>,
+    flag: &str,
+    args: &[String],
+) -> Result<(), CommandError>
+where
+    P: AliasDeclarationParser,
+    P::Error: std::error::Error + Send + Sync + 'static,
+{
+    for arg in args {
+        let (decl, defn) = arg.split_once('=').ok_or_else(|| {
+            user_error_with_hint(
+                format!(r#"Invalid {flag} "{arg}""#),
+                "The alias must be specified as NAME=DEFINITION.",
+            )
+        })?;
+        aliases_map
+            .insert(decl.trim(), defn.trim())
+            .map_err(|err| {
+                user_error_with_message(format!(r#"Failed to load {flag} "{decl}""#), err)
+            })?;
+    }
+    Ok(())
+}
+
 /// Helper to reformat content of log-like commands.
 #[derive(Clone, Debug)]
 pub enum LogContentFormat {
@@ -2122,19 +3162,57 @@ pub fn get_new_config_file_path(
     config_source: &ConfigSource,
     command: &CommandHelper,
 ) -> Result<PathBuf, CommandError> {
-    let edit_path = match config_source {
-        // TODO(#531): Special-case for editors that can't handle viewing directories?
-        ConfigSource::User => {
-            new_config_path()?.ok_or_else(|| user_error("No repo config path found to edit"))?
+    match config_source {
+        ConfigSource::User => command.user_config_file_path(),
+        ConfigSource::Repo => command.repo_config_file_path(),
+        _ => Err(user_error(format!(
+            "Can't get path for config source {config_source:?}"
+        ))),
+    }
+}
+
+/// Runs the `hooks.post-operation` command if configured. The new operation
+/// has already been committed, so a failing hook is only reported as a
+/// warning.
+fn run_post_operation_hook(
+    ui: &Ui,
+    settings: &UserSettings,
+    workspace_root: &Path,
+    op_id: &OperationId,
+) -> Result<(), CommandError> {
+    let Some(hook) = settings
+        .config()
+        .get::<CommandNameAndArgs>("hooks.post-operation")
+        .optional()
+        .map_err(|err| config_error_with_message("Invalid `hooks.post-operation`", err))?
+    else {
+        return Ok(());
+    };
+    let mut cmd = hook.to_command();
+    cmd.current_dir(workspace_root)
+        .env("JJ_OP_ID", op_id.hex())
+        .stdin(Stdio::null())
+        // Like Git, send the hook's output to stderr so it doesn't mix with
+        // the command's output.
+        .stdout(io::stderr());
+    tracing::info!(?cmd, "running post-operation hook");
+    match cmd.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => {
+            writeln!(
+                ui.warning_default(),
+                "Post-operation hook exited with {status}"
+            )?;
         }
-        ConfigSource::Repo => command.workspace_loader()?.repo_path().join("config.toml"),
-        _ => {
-            return Err(user_error(format!(
-                "Can't get path for config source {config_source:?}"
-            )));
+        Err(err) => {
+            writeln!(
+                ui.warning_default(),
+                "Failed to run post-operation hook '{name}': {err}",
+                name = hook.split_name(),
+            )?;
         }
-    };
-    Ok(edit_path)
+    }
+    Ok(())
 }
 
 pub fn run_ui_editor(settings: &UserSettings, edit_path: &PathBuf) -> Result<(), CommandError> {
@@ -2142,8 +3220,25 @@ pub fn run_ui_editor(settings: &UserSettings, edit_path: &PathBuf) -> Result<(),
         .config()
         .get("ui.editor")
         .map_err(|err| config_error_with_message("Invalid `ui.editor`", err))?;
+    // Some editors can't handle a non-UTF-8 path, and it couldn't be typed back
+    // by the user anyway.
+    if edit_path.to_str().is_none() {
+        return Err(user_error(format!(
+            "Cannot open {path} in editor: the path is not valid UTF-8",
+            path = edit_path.display()
+        )));
+    }
     let mut cmd = editor.to_command();
     cmd.arg(edit_path);
+    // Run the editor next to the file being edited so relative paths opened
+    // from within the editor don't depend on where jj was invoked.
+    if let Some(dir) = edit_path.parent().filter(|dir| dir.is_absolute()) {
+        cmd.current_dir(dir);
+    }
+    // Terminal editors may refuse to start without $TERM.
+    if env::var_os("TERM").is_none() {
+        cmd.env("TERM", "dumb");
+    }
     tracing::info!(?cmd, "running editor");
     let exit_status = cmd.status().map_err(|err| {
         user_error_with_message(
@@ -2212,9 +3307,17 @@ pub fn short_commit_hash(commit_id: &CommitId) -> String {
 }
 
 pub fn short_change_hash(change_id: &ChangeId) -> String {
-    // TODO: We could avoid the unwrap() and make this more efficient by converting
-    // straight from binary.
-    to_reverse_hex(&change_id.hex()[0..12]).unwrap()
+    try_short_change_hash(change_id).expect("change id should be at least 6 bytes long")
+}
+
+/// Like `short_change_hash()`, but returns an error if the `change_id` is too
+/// short to be abbreviated.
+pub fn try_short_change_hash(change_id: &ChangeId) -> Result<String, CommandError> {
+    let prefix = change_id
+        .as_bytes()
+        .get(..6)
+        .ok_or_else(|| internal_error(format!("Change id is too short: {}", change_id.hex())))?;
+    Ok(encode_reverse_hex(prefix))
 }
 
 pub fn short_operation_hash(operation_id: &OperationId) -> String {
@@ -2312,6 +3415,23 @@ impl fmt::Display for RemoteBranchNamePattern {
     }
 }
 
+/// Parses `branch@remote` patterns, reporting the first invalid one as a user
+/// error.
+pub fn parse_remote_branch_patterns(
+    args: &[String],
+) -> Result<Vec<RemoteBranchNamePattern>, CommandError> {
+    args.iter()
+        .map(|arg| {
+            arg.parse().map_err(|err: String| {
+                user_error_with_hint(
+                    format!("Invalid remote branch pattern \"{arg}\": {err}"),
+                    "Remote branches are specified in branch@remote form, e.g. main@origin",
+                )
+            })
+        })
+        .collect()
+}
+
 /// Jujutsu (An experimental VCS)
 ///
 /// To get started, see the tutorial at https://github.com/martinvonz/jj/blob/main/docs/tutorial.md.
@@ -2346,6 +3466,17 @@ pub struct GlobalArgs {
     /// implies `--ignore-working-copy`.
     #[arg(long, global = true)]
     pub ignore_working_copy: bool,
+    /// Load the repo at the working copy's operation if the working copy is
+    /// stale
+    ///
+    /// By default, a command fails if the working copy is stale, i.e. it
+    /// wasn't updated by an operation that changed the working-copy commit.
+    /// With this option, the repo is instead loaded at the operation the
+    /// working copy was last updated to, and a warning is printed. As with
+    /// `--ignore-working-copy`, the working copy is then neither snapshotted
+    /// nor updated.
+    #[arg(long, global = true)]
+    pub allow_stale_working_copy: bool,
     /// Allow rewriting immutable commits
     ///
     /// By default, Jujutsu prevents rewriting commits in the configured set of
@@ -2356,6 +3487,14 @@ pub struct GlobalArgs {
     /// `immutable_heads()` revset or the `immutable` template keyword.
     #[arg(long, global = true)]
     pub ignore_immutable: bool,
+    /// Don't export refs to Git automatically in a co-located repo
+    ///
+    /// By default, a command that changes the repo in a co-located repo also
+    /// exports the changed branches to Git. With this option, the export is
+    /// skipped, and the branches can be exported later with `jj git export`.
+    /// Git's `HEAD` is still updated. Importing refs from Git is not affected.
+    #[arg(long, global = true)]
+    pub no_git_export: bool,
     /// Operation to load the repo at
     ///
     /// Operation to load the repo at. By default, Jujutsu loads the repo at the
@@ -2365,7 +3504,12 @@ pub struct GlobalArgs {
     /// shown you when the given operation had just finished.
     ///
     /// Use `jj op log` to find the operation ID you want. Any unambiguous
-    /// prefix of the operation ID is enough.
+    /// prefix of the operation ID is enough. Use `--at-op=root` to load the
+    /// repo at its initial operation.
+    ///
+    /// You can also specify a time such as `--at-op='2 hours ago'` or
+    /// `--at-op='@{yesterday}'` to load the repo at the latest operation
+    /// completed at or before that time.
     ///
     /// When loading the repo at an earlier operation, the working copy will be
     /// ignored, as if `--ignore-working-copy` had been specified.
@@ -2376,9 +3520,38 @@ pub struct GlobalArgs {
     /// do that, but it is possible.
     #[arg(long, visible_alias = "at-op", global = true, default_value = "@")]
     pub at_operation: String,
+    /// Define a revset alias for this command only (can be repeated)
+    ///
+    /// The alias is specified as `NAME=DEFINITION`, for example
+    /// `--revset-alias 'mine()=author(me)'`. It takes precedence over the
+    /// aliases defined in the `revset-aliases` config table.
+    #[arg(long, value_name = "NAME=DEFINITION", global = true)]
+    pub revset_alias: Vec<String>,
+    /// Define a template alias for this command only (can be repeated)
+    ///
+    /// The alias is specified as `NAME=DEFINITION`, for example
+    /// `--template-alias 'short(c)=c.commit_id().short()'`. It takes
+    /// precedence over the aliases defined in the `template-aliases` config
+    /// table.
+    #[arg(long, value_name = "NAME=DEFINITION", global = true)]
+    pub template_alias: Vec<String>,
     /// Enable debug logging
+    ///
+    /// Internal errors are also reported with a backtrace.
     #[arg(long, global = true)]
     pub debug: bool,
+    /// Print the operation IDs before and after each mutating command
+    ///
+    /// The printed IDs can be passed to `jj op restore` to get back to a known
+    /// state. Nothing is printed if the command didn't create an operation.
+    #[arg(long, global = true)]
+    pub debug_op_ids: bool,
+    /// Exit with status 4 if a mutating command didn't change anything
+    ///
+    /// By default, a command which turns out to have nothing to do prints
+    /// "Nothing changed." and exits successfully.
+    #[arg(long, global = true)]
+    pub fail_on_no_change: bool,
 
     #[command(flatten)]
     pub early_args: EarlyArgs,
@@ -2399,16 +3572,86 @@ pub struct EarlyArgs {
     // Parsing with ignore_errors will crash if this is bool, so use
     // Option<bool>.
     pub quiet: Option<bool>,
+    /// Answer yes to all confirmation prompts
+    ///
+    /// This is useful for scripting commands that would otherwise ask for
+    /// confirmation. Prompts for other kinds of input are not affected.
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    // Parsing with ignore_errors will crash if this is bool, so use
+    // Option<bool>.
+    pub yes: Option<bool>,
     /// Disable the pager
     #[arg(long, value_name = "WHEN", global = true, action = ArgAction::SetTrue)]
     // Parsing with ignore_errors will crash if this is bool, so use
     // Option<bool>.
     pub no_pager: Option<bool>,
+    /// Don't load the user configuration
+    ///
+    /// Only the built-in defaults, the repo configuration, and
+    /// `--config-toml` arguments will be used. This ensures the command isn't
+    /// affected by personal settings of the invoking user.
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    // Parsing with ignore_errors will crash if this is bool, so use
+    // Option<bool>.
+    pub no_user_config: Option<bool>,
+    /// Sign commits created by this command with the given backend
+    ///
+    /// This overrides the `signing.backend` config, and signs all commits
+    /// authored by you as if `signing.sign-all` were set.
+    #[arg(
+        long,
+        value_name = "BACKEND",
+        global = true,
+        conflicts_with = "no_sign"
+    )]
+    pub sign_with: Option<String>,
+    /// Don't sign commits created by this command
+    #[arg(long, global = true, action = ArgAction::SetTrue)]
+    // Parsing with ignore_errors will crash if this is bool, so use
+    // Option<bool>.
+    pub no_sign: Option<bool>,
     /// Additional configuration options (can be repeated)
     //  TODO: Introduce a `--config` option with simpler syntax for simple
     //  cases, designed so that `--config ui.color=auto` works
     #[arg(long, value_name = "TOML", global = true)]
     pub config_toml: Vec<String>,
+    /// Additional configuration files to load (can be repeated)
+    ///
+    /// The files take precedence over the user and repo configs, but not over
+    /// `--config-toml`.
+    #[arg(long, value_name = "PATH", global = true, value_hint = clap::ValueHint::FilePath)]
+    pub config_file: Vec<PathBuf>,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct LimitArg {
+    /// Limit number of revisions to show
+    ///
+    /// Applied after revisions are filtered and reordered.
+    #[arg(long, short = 'n')]
+    pub limit: Option<usize>,
+    // TODO: Delete `-l` alias in jj 0.25+
+    #[arg(
+        short = 'l',
+        hide = true,
+        conflicts_with = "limit",
+        value_name = "LIMIT"
+    )]
+    pub deprecated_limit: Option<usize>,
+}
+
+impl LimitArg {
+    /// Returns the number of entries to show, warning if the deprecated `-l`
+    /// shorthand was used.
+    pub fn resolve(&self, ui: &Ui) -> io::Result<Option<usize>> {
+        if self.deprecated_limit.is_some() {
+            writeln!(
+                ui.warning_default(),
+                "The -l shorthand is deprecated, use -n instead."
+            )?;
+        }
+        Ok(self.limit.or(self.deprecated_limit))
+    }
 }
 
 /// Wrapper around revset expression argument.
@@ -2503,6 +3746,9 @@ fn resolve_default_command(
     Ok(string_args)
 }
 
+/// Maximum number of aliases that can be expanded for a single command line.
+const MAX_ALIAS_EXPANSION_DEPTH: usize = 100;
+
 fn resolve_aliases(
     ui: &Ui,
     config: &config::Config,
@@ -2522,7 +3768,8 @@ fn resolve_aliases(
         }
     }
 
-    let mut resolved_aliases = HashSet::new();
+    // Aliases expanded so far, in order
+    let mut resolved_aliases: Vec<String> = vec![];
     let mut real_commands = HashSet::new();
     for command in app.get_subcommands() {
         real_commands.insert(command.get_name().to_string());
@@ -2555,13 +3802,33 @@ fn resolve_aliases(
                         r#"Recursive alias definition involving "{alias_name}""#
                     )));
                 }
+                if resolved_aliases.len() >= MAX_ALIAS_EXPANSION_DEPTH {
+                    return Err(user_error_with_hint(
+                        format!(
+                            "Alias expansion exceeded the maximum depth of \
+                             {MAX_ALIAS_EXPANSION_DEPTH}"
+                        ),
+                        format!(
+                            "Expanded aliases: {}",
+                            resolved_aliases.iter().chain([&alias_name]).join(" -> ")
+                        ),
+                    ));
+                }
                 if let Some(value) = aliases_map.remove(&alias_name) {
                     if let Ok(alias_definition) = value.try_deserialize::<Vec<String>>() {
+                        let starts_with_flag = alias_definition
+                            .first()
+                            .is_some_and(|arg| arg.starts_with('-'));
                         assert!(string_args.ends_with(&alias_args));
                         string_args.truncate(string_args.len() - 1 - alias_args.len());
                         string_args.extend(alias_definition);
                         string_args.extend_from_slice(&alias_args);
-                        resolved_aliases.insert(alias_name.clone());
+                        resolved_aliases.push(alias_name.clone());
+                        if starts_with_flag {
+                            // The alias may consist of global flags only, in
+                            // which case the default command should apply.
+                            string_args = resolve_default_command(ui, config, app, string_args)?;
+                        }
                         continue;
                     } else {
                         return Err(user_error(format!(
@@ -2602,16 +3869,54 @@ fn handle_early_args(
     if args.quiet.unwrap_or_default() {
         args.config_toml.push(r#"ui.quiet=true"#.to_string());
     }
+    if args.yes.unwrap_or_default() {
+        args.config_toml.push("ui.assume-yes=true".to_owned());
+    }
     if args.no_pager.unwrap_or_default() {
         args.config_toml.push(r#"ui.paginate="never""#.to_owned());
     }
+    if let Some(backend) = &args.sign_with {
+        let backend = toml_edit::Value::from(backend.as_str());
+        args.config_toml.push(format!("signing.backend={backend}"));
+        args.config_toml.push("signing.sign-all=true".to_owned());
+    }
+    if args.no_sign.unwrap_or_default() {
+        args.config_toml
+            .push(r#"signing.backend="none""#.to_owned());
+        args.config_toml.push("signing.sign-all=false".to_owned());
+    }
+    if !args.config_file.is_empty() {
+        if let Some(path) = args.config_file.iter().find(|path| !path.is_file()) {
+            return Err(user_error(format!(
+                "Config file \"{}\" doesn't exist",
+                path.display()
+            )));
+        }
+        layered_configs.read_config_file_args(&args.config_file)?;
+        ui.reset(&layered_configs.merge())?;
+    }
     if !args.config_toml.is_empty() {
         layered_configs.parse_config_args(&args.config_toml)?;
         ui.reset(&layered_configs.merge())?;
+        for key in layered_configs.unknown_arg_override_keys()? {
+            writeln!(
+                ui.warning_default(),
+                "Unknown top-level config key `{key}` in --config-toml argument"
+            )?;
+        }
     }
     Ok(())
 }
 
+/// Checks if `--no-user-config` is specified. This has to be done before
+/// parsing the command line because the user config may define aliases.
+fn has_no_user_config_arg(args_os: ArgsOs) -> bool {
+    args_os
+        .skip(1)
+        .take_while(|arg| arg != "--")
+        .any(|arg| arg == "--no-user-config")
+}
+
 pub fn expand_args(
     ui: &Ui,
     app: &Command,
@@ -2649,6 +3954,7 @@ pub fn parse_args(
     if args.global_args.debug {
         // TODO: set up debug logging as early as possible
         tracing_subscription.enable_debug_logging()?;
+        enable_backtrace_capture();
     }
 
     Ok((matches, args))
@@ -2668,6 +3974,7 @@ pub struct CliRunner {
     tracing_subscription: TracingSubscription,
     app: Command,
     extra_configs: Vec<config::Config>,
+    env_config_prefix: String,
     store_factories: StoreFactories,
     working_copy_factories: WorkingCopyFactories,
     revset_extensions: RevsetExtensions,
@@ -2676,11 +3983,14 @@ pub struct CliRunner {
     dispatch_fn: CliDispatchFn,
     start_hook_fns: Vec<CliDispatchFn>,
     process_global_args_fns: Vec<ProcessGlobalArgsFn>,
+    config_migrations: Vec<(String, String)>,
+    status_sink: Option<StatusSink>,
 }
 
 type CliDispatchFn = Box<dyn FnOnce(&mut Ui, &CommandHelper) -> Result<(), CommandError>>;
 
-type ProcessGlobalArgsFn = Box<dyn FnOnce(&mut Ui, &ArgMatches) -> Result<(), CommandError>>;
+type ProcessGlobalArgsFn =
+    Box<dyn FnOnce(&mut Ui, &ArgMatches, &GlobalArgs) -> Result<(), CommandError>>;
 
 impl CliRunner {
     /// Initializes CLI environment and returns a builder. This should be called
@@ -2692,6 +4002,7 @@ impl CliRunner {
             tracing_subscription,
             app: crate::commands::default_app(),
             extra_configs: vec![],
+            env_config_prefix: "JJ".to_owned(),
             store_factories: StoreFactories::default(),
             working_copy_factories: default_working_copy_factories(),
             revset_extensions: Default::default(),
@@ -2700,6 +4011,8 @@ impl CliRunner {
             dispatch_fn: Box::new(crate::commands::run_command),
             start_hook_fns: vec![],
             process_global_args_fns: vec![],
+            config_migrations: vec![],
+            status_sink: None,
         }
     }
 
@@ -2715,6 +4028,44 @@ impl CliRunner {
         self
     }
 
+    /// Sets the prefix of the environment variables that override config
+    /// values, e.g. `MYTOOL` to read `MYTOOL_USER` instead of `JJ_USER`.
+    pub fn set_env_config_prefix(mut self, prefix: &str) -> Self {
+        self.env_config_prefix = prefix.to_owned();
+        self
+    }
+
+    /// Routes status messages such as "Working copy now at: ..." to the given
+    /// `sink` instead of stderr. Errors, warnings, and hints are still written
+    /// to stderr.
+    pub fn set_status_sink(mut self, sink: StatusSink) -> Self {
+        self.status_sink = Some(sink);
+        self
+    }
+
+    /// Sets the command to run if no subcommand is specified. The
+    /// `ui.default-command` config, if set by user, takes precedence.
+    pub fn set_default_command<S: Into<String>>(
+        self,
+        command: impl IntoIterator<Item = S>,
+    ) -> Self {
+        let command = command.into_iter().map(Into::into).collect_vec();
+        let config = config::Config::builder()
+            .set_default("ui.default-command", command)
+            .unwrap()
+            .build()
+            .unwrap();
+        self.add_extra_config(config)
+    }
+
+    /// Registers a renamed config key. If `old_key` is set, its value is
+    /// used as `new_key`, and a deprecation warning is printed.
+    pub fn add_config_migration(mut self, old_key: &str, new_key: &str) -> Self {
+        self.config_migrations
+            .push((old_key.to_owned(), new_key.to_owned()));
+        self
+    }
+
     /// Adds `StoreFactories` to be used.
     pub fn add_store_factories(mut self, store_factories: StoreFactories) -> Self {
         self.store_factories.merge(store_factories);
@@ -2790,15 +4141,29 @@ impl CliRunner {
     }
 
     /// Registers new global arguments in addition to the default ones.
-    pub fn add_global_args<A, F>(mut self, process_before: F) -> Self
+    pub fn add_global_args<A, F>(self, process_before: F) -> Self
     where
         A: clap::Args,
         F: FnOnce(&mut Ui, A) -> Result<(), CommandError> + 'static,
     {
-        let process_global_args_fn = move |ui: &mut Ui, matches: &ArgMatches| {
-            let custom_args = A::from_arg_matches(matches).unwrap();
+        self.add_global_args_with(move |ui, custom_args: A, _global_args| {
             process_before(ui, custom_args)
-        };
+        })
+    }
+
+    /// Registers new global arguments in addition to the default ones. Unlike
+    /// `add_global_args()`, the callback also receives the parsed built-in
+    /// global arguments such as `--repository` and `--at-operation`.
+    pub fn add_global_args_with<A, F>(mut self, process_before: F) -> Self
+    where
+        A: clap::Args,
+        F: FnOnce(&mut Ui, A, &GlobalArgs) -> Result<(), CommandError> + 'static,
+    {
+        let process_global_args_fn =
+            move |ui: &mut Ui, matches: &ArgMatches, global_args: &GlobalArgs| {
+                let custom_args = A::from_arg_matches(matches).unwrap();
+                process_before(ui, custom_args, global_args)
+            };
         self.app = A::augment_args(self.app);
         self.process_global_args_fns
             .push(Box::new(process_global_args_fn));
@@ -2810,6 +4175,7 @@ impl CliRunner {
         self,
         ui: &mut Ui,
         mut layered_configs: LayeredConfigs,
+        last_committed_op_id: LastCommittedOperation,
     ) -> Result<(), CommandError> {
         // `cwd` is canonicalized for consistency with `Workspace::workspace_root()` and
         // to easily compute relative paths between them.
@@ -2821,12 +4187,16 @@ impl CliRunner {
                     "Did you update to a commit where the directory doesn't exist?",
                 )
             })?;
+        if !has_no_user_config_arg(env::args_os()) {
+            layered_configs.read_user_config()?;
+        }
         // Use cwd-relative workspace configs to resolve default command and
         // aliases. WorkspaceLoader::init() won't do any heavy lifting other
         // than the path resolution.
-        let maybe_cwd_workspace_loader = WorkspaceLoader::init(find_workspace_dir(&cwd))
-            .map_err(|err| map_workspace_load_error(err, None));
-        layered_configs.read_user_config()?;
+        let ceiling_dirs = workspace_search_ceiling(&layered_configs.merge())?;
+        let maybe_cwd_workspace_loader =
+            WorkspaceLoader::init(find_workspace_dir(&cwd, &ceiling_dirs))
+                .map_err(|err| map_workspace_load_error(err, None));
         let mut repo_config_path = None;
         if let Ok(loader) = &maybe_cwd_workspace_loader {
             layered_configs.read_repo_config(loader.repo_path())?;
@@ -2853,12 +4223,12 @@ impl CliRunner {
         )
         .map_err(|err| map_clap_cli_error(err, ui, &layered_configs))?;
         for process_global_args_fn in self.process_global_args_fns {
-            process_global_args_fn(ui, &matches)?;
+            process_global_args_fn(ui, &matches, &args.global_args)?;
         }
 
         let maybe_workspace_loader = if let Some(path) = &args.global_args.repository {
             // Invalid -R path is an error. No need to proceed.
-            let loader = WorkspaceLoader::init(&cwd.join(path))
+            let loader = WorkspaceLoader::init(&cwd.join(expand_repository_path(path)))
                 .map_err(|err| map_workspace_load_error(err, Some(path)))?;
             layered_configs.read_repo_config(loader.repo_path())?;
             Ok(loader)
@@ -2866,6 +4236,15 @@ impl CliRunner {
             maybe_cwd_workspace_loader
         };
 
+        for (old_key, new_key) in &self.config_migrations {
+            if layered_configs.migrate_key(old_key, new_key)? {
+                writeln!(
+                    ui.warning_default(),
+                    "Config `{old_key}` is deprecated; use `{new_key}` instead"
+                )?;
+            }
+        }
+
         // Apply workspace configs and --config-toml arguments.
         let config = layered_configs.merge();
         ui.reset(&config)?;
@@ -2897,6 +4276,7 @@ impl CliRunner {
             maybe_workspace_loader,
             store_factories: self.store_factories,
             working_copy_factories: self.working_copy_factories,
+            last_committed_op_id,
         };
         for start_hook_fn in self.start_hook_fns {
             start_hook_fn(ui, &command_helper)?;
@@ -2906,7 +4286,14 @@ impl CliRunner {
 
     #[must_use]
     #[instrument(skip(self))]
-    pub fn run(mut self) -> ExitCode {
+    pub fn run(self) -> ExitCode {
+        self.run_and_report().exit_code
+    }
+
+    /// Runs the command like `run()`, and returns what the command did in
+    /// addition to the exit code.
+    #[must_use]
+    pub fn run_and_report(mut self) -> CommandOutcome {
         let builder = config::Config::builder().add_source(crate::config::default_config());
         let config = self
             .extra_configs
@@ -2914,13 +4301,58 @@ impl CliRunner {
             .fold(builder, |builder, config| builder.add_source(config))
             .build()
             .unwrap();
-        let layered_configs = LayeredConfigs::from_environment(config);
+        let layered_configs =
+            LayeredConfigs::from_environment_with_prefix(config, &self.env_config_prefix);
         let mut ui = Ui::with_config(&layered_configs.merge())
             .expect("default config should be valid, env vars are stringly typed");
-        let result = self.run_internal(&mut ui, layered_configs);
-        let exit_code = handle_command_result(&mut ui, result);
+        if let Some(sink) = self.status_sink.take() {
+            ui.set_status_sink(sink);
+        }
+        let tracing_subscription = self.tracing_subscription.clone();
+        tracing_subscription.print_init_warnings(&ui).ok();
+        let last_committed_op_id = LastCommittedOperation::default();
+        let result = self.run_internal(&mut ui, layered_configs, last_committed_op_id.clone());
+        let exit_code = handle_command_result(&mut ui, result.clone());
         ui.finalize_pager();
-        exit_code
+        tracing_subscription.print_span_timings(&ui).ok();
+        let operation_id = last_committed_op_id.take();
+        CommandOutcome {
+            exit_code,
+            operation_id,
+            error: result.err(),
+        }
+    }
+}
+
+/// Last operation committed by a command, shared between the helpers.
+#[derive(Clone, Debug, Default)]
+struct LastCommittedOperation(Arc<Mutex<Option<OperationId>>>);
+
+impl LastCommittedOperation {
+    fn set(&self, op_id: &OperationId) {
+        *self.0.lock().unwrap() = Some(op_id.clone());
+    }
+
+    fn take(&self) -> Option<OperationId> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// What a command run by `CliRunner::run_and_report()` did.
+#[derive(Debug)]
+pub struct CommandOutcome {
+    pub exit_code: ExitCode,
+    /// The last operation committed by the command, if any.
+    pub operation_id: Option<OperationId>,
+    /// The error the command failed with, if any. It has already been
+    /// reported to the user.
+    pub error: Option<CommandError>,
+}
+
+impl CommandOutcome {
+    /// Returns true if the command committed at least one transaction.
+    pub fn committed(&self) -> bool {
+        self.operation_id.is_some()
     }
 }
 
@@ -2932,6 +4364,12 @@ fn map_clap_cli_error(
     let Some(err) = cmd_err.error.downcast_ref::<clap::Error>() else {
         return cmd_err;
     };
+    let invalid_subcommand = match err.get(ContextKind::InvalidSubcommand) {
+        Some(ContextValue::String(name)) if err.kind() == ErrorKind::InvalidSubcommand => {
+            Some(name.clone())
+        }
+        _ => None,
+    };
     if let (Some(ContextValue::String(arg)), Some(ContextValue::String(value))) = (
         err.get(ContextKind::InvalidArg),
         err.get(ContextKind::InvalidValue),
@@ -2943,6 +4381,18 @@ fn map_clap_cli_error(
             }
         }
     }
+    if let Some(name) = invalid_subcommand {
+        // clap suggests similar built-in commands, but doesn't know about aliases.
+        let aliases = layered_configs
+            .merge()
+            .get_table("aliases")
+            .unwrap_or_default();
+        cmd_err.extend_hints(format_similarity_hint(&collect_similar(
+            &name,
+            aliases.keys(),
+        )));
+        cmd_err.add_hint("Use `jj -h` for a list of available commands.");
+    }
     cmd_err
 }
 
@@ -2957,3 +4407,42 @@ fn format_template_aliases_hint(template_aliases: &TemplateAliasesMap) -> String
     );
     hint
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_style_convert_separators() {
+        let convert = |style: PathStyle, path: &str, separator| {
+            style.convert_separators(path.to_owned(), separator)
+        };
+        assert_eq!(convert(PathStyle::Native, r"dir\file", '\\'), r"dir\file");
+        assert_eq!(convert(PathStyle::Slash, r"dir\file", '\\'), "dir/file");
+        assert_eq!(
+            convert(PathStyle::Slash, r"..\dir\file", '\\'),
+            "../dir/file"
+        );
+        assert_eq!(convert(PathStyle::Native, "dir/file", '/'), "dir/file");
+        assert_eq!(convert(PathStyle::Slash, "dir/file", '/'), "dir/file");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_ui_editor_non_utf8_path() {
+        use std::os::unix::ffi::OsStrExt as _;
+
+        let config = config::Config::builder()
+            .set_override("ui.editor", "true")
+            .unwrap()
+            .build()
+            .unwrap();
+        let settings = UserSettings::from_config(config);
+        let path = PathBuf::from(std::ffi::OsStr::from_bytes(b"dir/\xff"));
+        let err = run_ui_editor(&settings, &path).unwrap_err();
+        assert_eq!(
+            err.error.to_string(),
+            "Cannot open dir/\u{FFFD} in editor: the path is not valid UTF-8"
+        );
+    }
+}
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::backtrace::Backtrace;
 use std::io::Write as _;
 use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::{error, io, iter, str};
 
@@ -24,7 +26,7 @@ use jj_lib::git::{GitConfigParseError, GitExportError, GitImportError, GitRemote
 use jj_lib::gitignore::GitIgnoreError;
 use jj_lib::op_heads_store::OpHeadResolutionError;
 use jj_lib::op_store::OpStoreError;
-use jj_lib::op_walk::OpsetEvaluationError;
+use jj_lib::op_walk::{OpsetEvaluationError, OpsetResolutionError};
 use jj_lib::repo::{CheckOutCommitError, EditCommitError, RepoLoaderError, RewriteRootCommit};
 use jj_lib::repo_path::{RepoPathBuf, UiPathParseError};
 use jj_lib::revset::{
@@ -36,28 +38,47 @@ use jj_lib::working_copy::{ResetError, SnapshotError, WorkingCopyStateError};
 use jj_lib::workspace::WorkspaceInitError;
 use thiserror::Error;
 
+use crate::cli_util::short_operation_hash;
 use crate::diff_util::DiffRenderError;
 use crate::formatter::{FormatRecorder, Formatter};
 use crate::merge_tools::{ConflictResolveError, DiffEditError, MergeToolConfigError};
 use crate::revset_util::UserRevsetEvaluationError;
 use crate::template_parser::{TemplateParseError, TemplateParseErrorKind};
+use crate::text_util;
 use crate::ui::Ui;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CommandErrorKind {
     User,
+    /// No workspace was found at the specified or current directory. This is
+    /// reported in the same way as `User` error, but with a distinct exit
+    /// code.
+    NoWorkspace,
     Config,
     /// Invalid command line. The inner error type may be `clap::Error`.
     Cli,
     BrokenPipe,
+    /// The command didn't change anything, and `--fail-on-no-change` was
+    /// specified.
+    NothingChanged,
     Internal,
 }
 
+/// Whether to capture backtraces of internal errors.
+static CAPTURE_BACKTRACE: AtomicBool = AtomicBool::new(false);
+
+/// Makes internal errors constructed from now on capture backtraces.
+pub(crate) fn enable_backtrace_capture() {
+    CAPTURE_BACKTRACE.store(true, Ordering::Relaxed);
+}
+
 #[derive(Clone, Debug)]
 pub struct CommandError {
     pub kind: CommandErrorKind,
     pub error: Arc<dyn error::Error + Send + Sync>,
     pub hints: Vec<ErrorHint>,
+    /// Backtrace captured when an internal error was constructed.
+    pub backtrace: Option<Arc<Backtrace>>,
 }
 
 impl CommandError {
@@ -65,10 +86,17 @@ impl CommandError {
         kind: CommandErrorKind,
         err: impl Into<Box<dyn error::Error + Send + Sync>>,
     ) -> Self {
+        let backtrace =
+            if kind == CommandErrorKind::Internal && CAPTURE_BACKTRACE.load(Ordering::Relaxed) {
+                Some(Arc::new(Backtrace::force_capture()))
+            } else {
+                None
+            };
         CommandError {
             kind,
             error: Arc::from(err.into()),
             hints: vec![],
+            backtrace,
         }
     }
 
@@ -143,6 +171,10 @@ pub fn user_error(err: impl Into<Box<dyn error::Error + Send + Sync>>) -> Comman
     CommandError::new(CommandErrorKind::User, err)
 }
 
+pub fn no_workspace_error(err: impl Into<Box<dyn error::Error + Send + Sync>>) -> CommandError {
+    CommandError::new(CommandErrorKind::NoWorkspace, err)
+}
+
 pub fn user_error_with_hint(
     err: impl Into<Box<dyn error::Error + Send + Sync>>,
     hint: impl Into<String>,
@@ -183,7 +215,15 @@ pub fn internal_error_with_message(
     CommandError::with_message(CommandErrorKind::Internal, message, source)
 }
 
-fn format_similarity_hint<S: AsRef<str>>(candidates: &[S]) -> Option<String> {
+/// Wraps an error in parsing or evaluating the user-configured
+/// `immutable_heads()` so it points at the config.
+pub fn immutable_heads_config_error(
+    err: impl Into<Box<dyn error::Error + Send + Sync>>,
+) -> CommandError {
+    config_error_with_message("Invalid `revset-aliases.immutable_heads()`", err)
+}
+
+pub(crate) fn format_similarity_hint<S: AsRef<str>>(candidates: &[S]) -> Option<String> {
     match candidates {
         [] => None,
         names => {
@@ -218,21 +258,33 @@ impl From<crate::config::ConfigError> for CommandError {
     }
 }
 
+fn rewrite_root_commit_error(err: impl Into<Box<dyn error::Error + Send + Sync>>) -> CommandError {
+    user_error_with_hint(err, "The root commit is immutable and can't be rewritten.")
+}
+
 impl From<RewriteRootCommit> for CommandError {
     fn from(err: RewriteRootCommit) -> Self {
-        internal_error_with_message("Attempted to rewrite the root commit", err)
+        rewrite_root_commit_error(err)
     }
 }
 
 impl From<EditCommitError> for CommandError {
     fn from(err: EditCommitError) -> Self {
-        internal_error_with_message("Failed to edit a commit", err)
+        match err {
+            EditCommitError::RewriteRootCommit => rewrite_root_commit_error(err),
+            _ => internal_error_with_message("Failed to edit a commit", err),
+        }
     }
 }
 
 impl From<CheckOutCommitError> for CommandError {
     fn from(err: CheckOutCommitError) -> Self {
-        internal_error_with_message("Failed to check out a commit", err)
+        match err {
+            CheckOutCommitError::EditCommit(err @ EditCommitError::RewriteRootCommit) => {
+                rewrite_root_commit_error(err)
+            }
+            _ => internal_error_with_message("Failed to check out a commit", err),
+        }
     }
 }
 
@@ -285,7 +337,12 @@ impl From<OpHeadResolutionError> for CommandError {
 impl From<OpsetEvaluationError> for CommandError {
     fn from(err: OpsetEvaluationError) -> Self {
         match err {
-            OpsetEvaluationError::OpsetResolution(err) => user_error(err),
+            OpsetEvaluationError::OpsetResolution(err) => {
+                let hint = opset_resolution_error_hint(&err);
+                let mut cmd_err = user_error(err);
+                cmd_err.extend_hints(hint);
+                cmd_err
+            }
             OpsetEvaluationError::OpHeadResolution(err) => err.into(),
             OpsetEvaluationError::OpStore(err) => err.into(),
         }
@@ -606,6 +663,21 @@ fn revset_parse_error_hint(err: &RevsetParseError) -> Option<String> {
     }
 }
 
+fn opset_resolution_error_hint(err: &OpsetResolutionError) -> Option<String> {
+    match err {
+        OpsetResolutionError::AmbiguousIdPrefix {
+            prefix: _,
+            candidates,
+        } => format_similarity_hint(&candidates.iter().map(short_operation_hash).collect_vec()),
+        OpsetResolutionError::MultipleOperations(_)
+        | OpsetResolutionError::EmptyOperations(_)
+        | OpsetResolutionError::InvalidIdPrefix(_)
+        | OpsetResolutionError::NoSuchOperation(_)
+        | OpsetResolutionError::InvalidTimeExpression(_)
+        | OpsetResolutionError::NoOperationAtTime(_) => None,
+    }
+}
+
 fn revset_resolution_error_hint(err: &RevsetResolutionError) -> Option<String> {
     match err {
         RevsetResolutionError::NoSuchRevision {
@@ -646,7 +718,51 @@ fn template_parse_error_hint(err: &TemplateParseError) -> Option<String> {
     }
 }
 
+/// Values expected by config keys, used to explain an invalid config value.
+const CONFIG_VALUE_HINTS: &[(&str, &str)] = &[
+    (
+        "fix.tool-command",
+        "a command string or a list of the command and its arguments",
+    ),
+    ("operation.merge-order", "one of `timestamp` or `id`"),
+    ("revsets.resolve-divergent", "one of `error` or `latest`"),
+    (
+        "ui.editor",
+        "a command string or a list of the command and its arguments",
+    ),
+    ("ui.hint-list-limit", "a non-negative integer"),
+    ("ui.max-conflict-report", "a non-negative integer"),
+    (
+        "ui.pager",
+        "a command string or a list of the command and its arguments",
+    ),
+    ("ui.paginate", "one of `never`, `auto`, or `auto-long`"),
+    ("ui.rebase-report-threshold", "a non-negative integer"),
+    (
+        "working-copy.clean",
+        "one of `empty-diff` or `empty-commit`",
+    ),
+    ("working-copy.lock-timeout", "a number of milliseconds"),
+];
+
+/// Returns a hint about the expected value if the config error mentions a
+/// known config key.
+fn config_value_hint(err: &(dyn error::Error + 'static)) -> Option<String> {
+    let messages = iter::successors(Some(err), |err| err.source())
+        .map(|err| err.to_string())
+        .collect_vec();
+    CONFIG_VALUE_HINTS.iter().find_map(|(key, expected)| {
+        let quoted_key = format!("`{key}`");
+        messages
+            .iter()
+            .any(|message| message.contains(&quoted_key))
+            .then(|| format!("Check the {quoted_key} value; expected {expected}."))
+    })
+}
+
 const BROKEN_PIPE_EXIT_CODE: u8 = 3;
+const NOTHING_CHANGED_EXIT_CODE: u8 = 4;
+const NO_WORKSPACE_EXIT_CODE: u8 = 5;
 
 pub(crate) fn handle_command_result(ui: &mut Ui, result: Result<(), CommandError>) -> ExitCode {
     try_handle_command_result(ui, result).unwrap_or_else(|_| ExitCode::from(BROKEN_PIPE_EXIT_CODE))
@@ -666,12 +782,20 @@ fn try_handle_command_result(
             print_error(ui, "Error: ", err, hints)?;
             Ok(ExitCode::from(1))
         }
+        CommandErrorKind::NoWorkspace => {
+            print_error(ui, "Error: ", err, hints)?;
+            Ok(ExitCode::from(NO_WORKSPACE_EXIT_CODE))
+        }
         CommandErrorKind::Config => {
             print_error(ui, "Config error: ", err, hints)?;
-            writeln!(
-                ui.stderr_formatter().labeled("hint"),
-                "For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md."
-            )?;
+            if let Some(hint) = config_value_hint(err.as_ref()) {
+                writeln!(ui.hint_default(), "{hint}")?;
+            } else {
+                writeln!(
+                    ui.stderr_formatter().labeled("hint"),
+                    "For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md."
+                )?;
+            }
             Ok(ExitCode::from(1))
         }
         CommandErrorKind::Cli => {
@@ -686,8 +810,15 @@ fn try_handle_command_result(
             // A broken pipe is not an error, but a signal to exit gracefully.
             Ok(ExitCode::from(BROKEN_PIPE_EXIT_CODE))
         }
+        CommandErrorKind::NothingChanged => {
+            // "Nothing changed." has already been printed.
+            Ok(ExitCode::from(NOTHING_CHANGED_EXIT_CODE))
+        }
         CommandErrorKind::Internal => {
             print_error(ui, "Internal error: ", err, hints)?;
+            if let Some(backtrace) = &cmd_err.backtrace {
+                print_error_backtrace(ui, backtrace)?;
+            }
             Ok(ExitCode::from(255))
         }
     }
@@ -699,7 +830,10 @@ fn print_error(
     err: &dyn error::Error,
     hints: &[ErrorHint],
 ) -> io::Result<()> {
-    writeln!(ui.error_with_heading(heading), "{err}")?;
+    write_message(ui, "error", |formatter| {
+        write!(formatter.labeled("heading"), "{heading}")?;
+        writeln!(formatter, "{err}")
+    })?;
     print_error_sources(ui, err.source())?;
     print_error_hints(ui, hints)?;
     Ok(())
@@ -709,25 +843,33 @@ fn print_error_sources(ui: &Ui, source: Option<&dyn error::Error>) -> io::Result
     let Some(err) = source else {
         return Ok(());
     };
-    ui.stderr_formatter()
-        .with_label("error_source", |formatter| {
-            if err.source().is_none() {
-                write!(formatter.labeled("heading"), "Caused by: ")?;
+    write_message(ui, "error_source", |formatter| {
+        if err.source().is_none() {
+            write!(formatter.labeled("heading"), "Caused by: ")?;
+            writeln!(formatter, "{err}")?;
+        } else {
+            writeln!(formatter.labeled("heading"), "Caused by:")?;
+            for (i, err) in iter::successors(Some(err), |err| err.source()).enumerate() {
+                write!(formatter.labeled("heading"), "{}: ", i + 1)?;
                 writeln!(formatter, "{err}")?;
-            } else {
-                writeln!(formatter.labeled("heading"), "Caused by:")?;
-                for (i, err) in iter::successors(Some(err), |err| err.source()).enumerate() {
-                    write!(formatter.labeled("heading"), "{}: ", i + 1)?;
-                    writeln!(formatter, "{err}")?;
-                }
             }
+        }
+        Ok(())
+    })
+}
+
+fn print_error_backtrace(ui: &Ui, backtrace: &Backtrace) -> io::Result<()> {
+    ui.stderr_formatter()
+        .with_label("error_source", |formatter| {
+            writeln!(formatter.labeled("heading"), "Backtrace:")?;
+            write!(formatter, "{backtrace}")?;
             Ok(())
         })
 }
 
 fn print_error_hints(ui: &Ui, hints: &[ErrorHint]) -> io::Result<()> {
     for hint in hints {
-        ui.stderr_formatter().with_label("hint", |formatter| {
+        write_message(ui, "hint", |formatter| {
             write!(formatter.labeled("heading"), "Hint: ")?;
             match hint {
                 ErrorHint::PlainText(message) => {
@@ -749,6 +891,24 @@ fn print_error_hints(ui: &Ui, hints: &[ErrorHint]) -> io::Result<()> {
     Ok(())
 }
 
+/// Writes a labeled message to stderr, wrapping it at the terminal width if
+/// `ui.wrap-errors` is enabled. Existing newlines are preserved.
+fn write_message(
+    ui: &Ui,
+    label: &str,
+    write: impl FnOnce(&mut dyn Formatter) -> io::Result<()>,
+) -> io::Result<()> {
+    ui.stderr_formatter().with_label(label, |formatter| {
+        if let Some(width) = ui.error_wrap_width() {
+            let mut recorder = FormatRecorder::new();
+            write(&mut recorder)?;
+            text_util::write_wrapped(formatter, &recorder, width)
+        } else {
+            write(formatter)
+        }
+    })
+}
+
 fn handle_clap_error(ui: &mut Ui, err: &clap::Error, hints: &[ErrorHint]) -> io::Result<ExitCode> {
     let clap_str = if ui.color() {
         err.render().ansi().to_string()
@@ -135,11 +135,10 @@ pub(crate) fn cmd_bench(
     match subcommand {
         BenchCommand::CommonAncestors(args) => {
             let workspace_command = command.workspace_helper(ui)?;
-            let commit1 = workspace_command.resolve_single_rev(&args.revision1)?;
-            let commit2 = workspace_command.resolve_single_rev(&args.revision2)?;
+            let commit_id1 = workspace_command.resolve_single_commit_id(&args.revision1)?;
+            let commit_id2 = workspace_command.resolve_single_commit_id(&args.revision2)?;
             let index = workspace_command.repo().index();
-            let routine =
-                || index.common_ancestors(&[commit1.id().clone()], &[commit2.id().clone()]);
+            let routine = || index.common_ancestors(&[commit_id1.clone()], &[commit_id2.clone()]);
             run_bench(
                 ui,
                 &format!("commonancestors-{}-{}", args.revision1, args.revision2),
@@ -149,10 +148,10 @@ pub(crate) fn cmd_bench(
         }
         BenchCommand::IsAncestor(args) => {
             let workspace_command = command.workspace_helper(ui)?;
-            let ancestor_commit = workspace_command.resolve_single_rev(&args.ancestor)?;
-            let descendant_commit = workspace_command.resolve_single_rev(&args.descendant)?;
+            let ancestor_id = workspace_command.resolve_single_commit_id(&args.ancestor)?;
+            let descendant_id = workspace_command.resolve_single_commit_id(&args.descendant)?;
             let index = workspace_command.repo().index();
-            let routine = || index.is_ancestor(ancestor_commit.id(), descendant_commit.id());
+            let routine = || index.is_ancestor(&ancestor_id, &descendant_id);
             run_bench(
                 ui,
                 &format!("isancestor-{}-{}", args.ancestor, args.descendant),
@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use itertools::Itertools as _;
 
 use super::find_remote_branches;
-use crate::cli_util::{CommandHelper, RemoteBranchNamePattern};
+use crate::cli_util::{parse_remote_branch_patterns, CommandHelper};
 use crate::command_error::CommandError;
 use crate::commit_templater::{CommitTemplateLanguage, RefName};
 use crate::ui::Ui;
@@ -37,7 +37,7 @@ pub struct BranchTrackArgs {
     ///
     /// Examples: branch@remote, glob:main@*, glob:jjfan-*@upstream
     #[arg(required = true, value_name = "BRANCH@REMOTE")]
-    names: Vec<RemoteBranchNamePattern>,
+    names: Vec<String>,
 }
 
 pub fn cmd_branch_track(
@@ -45,10 +45,11 @@ pub fn cmd_branch_track(
     command: &CommandHelper,
     args: &BranchTrackArgs,
 ) -> Result<(), CommandError> {
+    let name_patterns = parse_remote_branch_patterns(&args.names)?;
     let mut workspace_command = command.workspace_helper(ui)?;
     let view = workspace_command.repo().view();
     let mut names = Vec::new();
-    for (name, remote_ref) in find_remote_branches(view, &args.names)? {
+    for (name, remote_ref) in find_remote_branches(view, &name_patterns)? {
         if remote_ref.is_tracking() {
             writeln!(
                 ui.warning_default(),
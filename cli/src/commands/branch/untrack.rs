@@ -16,7 +16,7 @@ use itertools::Itertools as _;
 use jj_lib::git;
 
 use super::find_remote_branches;
-use crate::cli_util::{CommandHelper, RemoteBranchNamePattern};
+use crate::cli_util::{parse_remote_branch_patterns, CommandHelper};
 use crate::command_error::CommandError;
 use crate::ui::Ui;
 
@@ -34,7 +34,7 @@ pub struct BranchUntrackArgs {
     ///
     /// Examples: branch@remote, glob:main@*, glob:jjfan-*@upstream
     #[arg(required = true, value_name = "BRANCH@REMOTE")]
-    names: Vec<RemoteBranchNamePattern>,
+    names: Vec<String>,
 }
 
 pub fn cmd_branch_untrack(
@@ -42,10 +42,11 @@ pub fn cmd_branch_untrack(
     command: &CommandHelper,
     args: &BranchUntrackArgs,
 ) -> Result<(), CommandError> {
+    let name_patterns = parse_remote_branch_patterns(&args.names)?;
     let mut workspace_command = command.workspace_helper(ui)?;
     let view = workspace_command.repo().view();
     let mut names = Vec::new();
-    for (name, remote_ref) in find_remote_branches(view, &args.names)? {
+    for (name, remote_ref) in find_remote_branches(view, &name_patterns)? {
         if name.remote == git::REMOTE_NAME_FOR_LOCAL_GIT_REPO {
             // This restriction can be lifted if we want to support untracked @git branches.
             writeln!(
@@ -49,10 +49,9 @@ pub(crate) fn cmd_commit(
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
 
-    let commit_id = workspace_command
-        .get_wc_commit_id()
+    let commit = workspace_command
+        .wc_commit()?
         .ok_or_else(|| user_error("This command requires a working copy"))?;
-    let commit = workspace_command.repo().store().get_commit(commit_id)?;
     let matcher = workspace_command
         .parse_file_patterns(&args.paths)?
         .to_matcher();
@@ -99,7 +98,12 @@ new working-copy commit.
     let description = if !args.message_paragraphs.is_empty() {
         join_message_paragraphs(&args.message_paragraphs)
     } else {
-        edit_description(tx.base_repo(), &template, command.settings())?
+        edit_description(
+            tx.base_workspace_helper(),
+            &commit,
+            &template,
+            command.settings(),
+        )?
     };
 
     let new_commit = tx
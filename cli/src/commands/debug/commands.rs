@@ -0,0 +1,115 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use clap::Command;
+use jj_lib::settings::ConfigResultExt as _;
+
+use crate::cli_util::CommandHelper;
+use crate::command_error::{internal_error, CommandError};
+use crate::ui::Ui;
+
+/// List the available commands and aliases
+#[derive(clap::Args, Clone, Debug)]
+pub struct DebugCommandsArgs {
+    #[arg(long, value_enum, default_value = "text")]
+    format: CommandsFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum CommandsFormat {
+    /// One command or alias per line
+    Text,
+    /// A JSON array of objects
+    Json,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum CommandKind {
+    /// Built-in command
+    Command,
+    /// Alias defined in the `[aliases]` config table
+    Alias,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+struct CommandEntry {
+    /// Space-separated path of the (sub)command, e.g. "git push".
+    name: String,
+    kind: CommandKind,
+    /// Alternative names of the last path component.
+    aliases: Vec<String>,
+    hidden: bool,
+}
+
+pub fn cmd_debug_commands(
+    ui: &mut Ui,
+    command: &CommandHelper,
+    args: &DebugCommandsArgs,
+) -> Result<(), CommandError> {
+    let mut entries = vec![];
+    collect_subcommands(command.app(), "", &mut entries);
+    let config = command.settings().config();
+    // Aliases may also be defined in the legacy `[alias]` table.
+    let mut alias_names = BTreeSet::new();
+    for key in ["aliases", "alias"] {
+        if let Some(table) = config.get_table(key).optional()? {
+            alias_names.extend(table.into_keys());
+        }
+    }
+    entries.extend(alias_names.into_iter().map(|name| CommandEntry {
+        name,
+        kind: CommandKind::Alias,
+        aliases: vec![],
+        hidden: false,
+    }));
+
+    match args.format {
+        CommandsFormat::Text => {
+            for entry in &entries {
+                let kind = match entry.kind {
+                    CommandKind::Command => "command",
+                    CommandKind::Alias => "alias",
+                };
+                write!(ui.stdout(), "{kind}\t{}", entry.name)?;
+                if !entry.aliases.is_empty() {
+                    write!(ui.stdout(), "\t{}", entry.aliases.join(","))?;
+                }
+                writeln!(ui.stdout())?;
+            }
+        }
+        CommandsFormat::Json => {
+            let json = serde_json::to_string_pretty(&entries).map_err(internal_error)?;
+            writeln!(ui.stdout(), "{json}")?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_subcommands(app: &Command, prefix: &str, entries: &mut Vec<CommandEntry>) {
+    for subcommand in app.get_subcommands() {
+        let name = format!("{prefix}{}", subcommand.get_name());
+        entries.push(CommandEntry {
+            name: name.clone(),
+            kind: CommandKind::Command,
+            aliases: subcommand.get_all_aliases().map(str::to_owned).collect(),
+            hidden: subcommand.is_hide_set(),
+        });
+        collect_subcommands(subcommand, &format!("{name} "), entries);
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
 
 use jj_lib::object_id::ObjectId;
 use tracing::instrument;
@@ -80,10 +80,10 @@ pub(crate) fn cmd_describe(
     } else {
         let template =
             description_template_for_describe(ui, command.settings(), &workspace_command, &commit)?;
-        edit_description(workspace_command.repo(), &template, command.settings())?
+        edit_description(&workspace_command, &commit, &template, command.settings())?
     };
     if description == *commit.description() && !args.reset_author {
-        writeln!(ui.status(), "Nothing changed.")?;
+        command.report_nothing_changed(ui)?;
     } else {
         let mut tx = workspace_command.start_transaction();
         let mut commit_builder = tx
@@ -103,7 +103,7 @@ don't make any changes, then the operation will be aborted.",
     let tree = target_commit.tree()?;
     let tree_id = diff_editor.edit(&base_tree, &tree, &EverythingMatcher, Some(&instructions))?;
     if tree_id == *target_commit.tree_id() {
-        writeln!(ui.status(), "Nothing changed.")?;
+        command.report_nothing_changed(ui)?;
     } else {
         let mut_repo = tx.mut_repo();
         let new_commit = mut_repo
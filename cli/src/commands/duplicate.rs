@@ -78,9 +78,10 @@ pub(crate) fn cmd_duplicate(
     }
 
     if let Some(mut formatter) = ui.status_formatter() {
+        let template = tx.commit_summary_template();
         for (old_id, new_commit) in &duplicated_old_to_new {
             write!(formatter, "Duplicated {} as ", short_commit_hash(old_id))?;
-            tx.write_commit_summary(formatter.as_mut(), new_commit)?;
+            template.format(new_commit, formatter.as_mut())?;
             writeln!(formatter)?;
         }
     }
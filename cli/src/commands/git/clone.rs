@@ -174,6 +174,7 @@ fn do_git_clone(
     } else {
         Workspace::init_internal_git(command.settings(), wc_path)?
     };
+    command.record_committed_operation(repo.op_id());
     let git_repo = get_git_repo(repo.store())?;
     writeln!(
         ui.status(),
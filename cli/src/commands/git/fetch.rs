@@ -14,14 +14,13 @@
 
 use itertools::Itertools;
 use jj_lib::git::{self, GitFetchError};
-use jj_lib::repo::Repo;
 use jj_lib::settings::{ConfigResultExt as _, UserSettings};
 use jj_lib::str_util::StringPattern;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::{user_error, user_error_with_hint, CommandError};
 use crate::commands::git::{get_single_remote, map_git_error};
-use crate::git_util::{get_git_repo, print_git_import_stats, with_remote_git_callbacks};
+use crate::git_util::{print_git_import_stats, with_remote_git_callbacks};
 use crate::ui::Ui;
 
 /// Fetch from a Git remote
@@ -52,7 +51,7 @@ pub fn cmd_git_fetch(
     args: &GitFetchArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let git_repo = get_git_repo(workspace_command.repo().store())?;
+    let git_repo = workspace_command.git_repo()?;
     let remotes = if args.all_remotes {
         get_all_remotes(&git_repo)?
     } else if args.remotes.is_empty() {
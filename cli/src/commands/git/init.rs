@@ -139,12 +139,14 @@ pub fn do_init(
         GitInitMode::Colocate => {
             let (workspace, repo) =
                 Workspace::init_colocated_git(command.settings(), workspace_root)?;
+            command.record_committed_operation(repo.op_id());
             let workspace_command = command.for_loaded_repo(ui, workspace, repo)?;
             maybe_add_gitignore(&workspace_command)?;
         }
         GitInitMode::External(git_repo_path) => {
             let (workspace, repo) =
                 Workspace::init_external_git(command.settings(), workspace_root, git_repo_path)?;
+            command.record_committed_operation(repo.op_id());
             // Import refs first so all the reachable commits are indexed in
             // chronological order.
             let colocated = is_colocated_git_workspace(&workspace, &repo);
@@ -163,10 +165,11 @@ pub fn do_init(
                     tx.finish(ui, "import git head")?;
                 }
             }
-            print_trackable_remote_branches(ui, workspace_command.repo().view())?;
+            print_trackable_remote_branches(ui, &workspace_command)?;
         }
         GitInitMode::Internal => {
-            Workspace::init_internal_git(command.settings(), workspace_root)?;
+            let (_, repo) = Workspace::init_internal_git(command.settings(), workspace_root)?;
+            command.record_committed_operation(repo.op_id());
         }
     }
     Ok(())
@@ -183,7 +186,16 @@ fn init_git_refs(
     repo: Arc<ReadonlyRepo>,
     colocated: bool,
 ) -> Result<Arc<ReadonlyRepo>, CommandError> {
-    let mut tx = start_repo_transaction(&repo, command.settings(), command.string_args());
+    let record_display_args = command
+        .settings()
+        .config()
+        .get_bool("operation.record-display-args")?;
+    let mut tx = start_repo_transaction(
+        &repo,
+        command.settings(),
+        command.string_args(),
+        record_display_args,
+    );
     // There should be no old refs to abandon, but enforce it.
     let mut git_settings = command.settings().git_settings();
     git_settings.abandon_unreachable_commits = false;
@@ -204,6 +216,7 @@ fn init_git_refs(
         print_failed_git_export(ui, &failed_branches)?;
     }
     let repo = tx.commit("import git refs");
+    command.record_committed_operation(repo.op_id());
     writeln!(
         ui.status(),
         "Done importing changes from the underlying Git repo."
@@ -31,12 +31,12 @@ use jj_lib::str_util::StringPattern;
 use jj_lib::view::View;
 
 use crate::cli_util::{
-    short_change_hash, short_commit_hash, CommandHelper, RevisionArg, WorkspaceCommandHelper,
+    short_commit_hash, try_short_change_hash, CommandHelper, RevisionArg, WorkspaceCommandHelper,
     WorkspaceCommandTransaction,
 };
 use crate::command_error::{user_error, user_error_with_hint, CommandError};
 use crate::commands::git::{get_single_remote, map_git_error};
-use crate::git_util::{get_git_repo, with_remote_git_callbacks, GitSidebandProgressMessageWriter};
+use crate::git_util::{with_remote_git_callbacks, GitSidebandProgressMessageWriter};
 use crate::revset_util;
 use crate::ui::Ui;
 
@@ -126,7 +126,7 @@ pub fn cmd_git_push(
     args: &GitPushArgs,
 ) -> Result<(), CommandError> {
     let mut workspace_command = command.workspace_helper(ui)?;
-    let git_repo = get_git_repo(workspace_command.repo().store())?;
+    let git_repo = workspace_command.git_repo()?;
 
     let remote = if let Some(name) = &args.remote {
         name.clone()
@@ -235,8 +235,7 @@ pub fn cmd_git_push(
         );
     }
     if branch_updates.is_empty() {
-        writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
+        return command.report_nothing_changed(ui);
     }
 
     let mut branch_push_direction = HashMap::new();
@@ -480,7 +479,7 @@ fn update_change_branches(
 
     for commit in all_commits {
         let workspace_command = tx.base_workspace_helper();
-        let short_change_id = short_change_hash(commit.change_id());
+        let short_change_id = try_short_change_hash(commit.change_id())?;
         let mut branch_name = format!("{branch_prefix}{}", commit.change_id().hex());
         let view = tx.base_repo().view();
         if view.get_local_branch(&branch_name).is_absent() {
@@ -13,11 +13,9 @@
 // limitations under the License.
 
 use jj_lib::git;
-use jj_lib::repo::Repo;
 
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
-use crate::git_util::get_git_repo;
 use crate::ui::Ui;
 
 /// Add a Git remote
@@ -35,8 +33,7 @@ pub fn cmd_git_remote_add(
     args: &GitRemoteAddArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let repo = workspace_command.repo();
-    let git_repo = get_git_repo(repo.store())?;
+    let git_repo = workspace_command.git_repo()?;
     git::add_remote(&git_repo, &args.remote, &args.url)?;
     Ok(())
 }
@@ -14,11 +14,8 @@
 
 use std::io::Write;
 
-use jj_lib::repo::Repo;
-
 use crate::cli_util::CommandHelper;
 use crate::command_error::CommandError;
-use crate::git_util::get_git_repo;
 use crate::ui::Ui;
 
 /// List Git remotes
@@ -31,8 +28,7 @@ pub fn cmd_git_remote_list(
     _args: &GitRemoteListArgs,
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
-    let repo = workspace_command.repo();
-    let git_repo = get_git_repo(repo.store())?;
+    let git_repo = workspace_command.git_repo()?;
     for remote_name in git_repo.remotes()?.iter().flatten() {
         let remote = git_repo.find_remote(remote_name)?;
         writeln!(
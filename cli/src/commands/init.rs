@@ -74,7 +74,8 @@ Use `jj git init` instead"
 Set `ui.allow-init-native` to allow initializing a repo with the native backend.",
             ));
         }
-        Workspace::init_local(command.settings(), &wc_path)?;
+        let (_, repo) = Workspace::init_local(command.settings(), &wc_path)?;
+        command.record_committed_operation(repo.op_id());
     }
 
     let relative_wc_path = file_util::relative_path(cwd, &wc_path);
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use jj_lib::backend::CommitId;
 use jj_lib::graph::{GraphEdgeType, ReverseGraphIterator, TopoGroupedGraphIterator};
 use jj_lib::repo::Repo;
 use jj_lib::revset::{RevsetExpression, RevsetFilterPredicate, RevsetIteratorExt};
 use tracing::instrument;
 
-use crate::cli_util::{format_template, CommandHelper, LogContentFormat, RevisionArg};
+use crate::cli_util::{
+    format_template, read_template_file, CommandHelper, LimitArg, LogContentFormat, RevisionArg,
+};
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::diff_util::DiffFormatArgs;
@@ -46,19 +50,8 @@ pub(crate) struct LogArgs {
     /// Show revisions in the opposite order (older revisions first)
     #[arg(long)]
     reversed: bool,
-    /// Limit number of revisions to show
-    ///
-    /// Applied after revisions are filtered and reordered.
-    #[arg(long, short = 'n')]
-    limit: Option<usize>,
-    // TODO: Delete `-l` alias in jj 0.25+
-    #[arg(
-        short = 'l',
-        hide = true,
-        conflicts_with = "limit",
-        value_name = "LIMIT"
-    )]
-    deprecated_limit: Option<usize>,
+    #[command(flatten)]
+    limit: LimitArg,
     /// Don't show the graph, show a flat list of revisions
     #[arg(long)]
     no_graph: bool,
@@ -67,6 +60,14 @@ pub(crate) struct LogArgs {
     /// For the syntax, see https://github.com/martinvonz/jj/blob/main/docs/templates.md
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Render each revision using the template read from the given file
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "template",
+        value_hint = clap::ValueHint::FilePath
+    )]
+    template_file: Option<PathBuf>,
     /// Show patch
     #[arg(long, short = 'p')]
     patch: bool,
@@ -120,9 +121,10 @@ pub(crate) fn cmd_log(
     let node_template;
     {
         let language = workspace_command.commit_template_language()?;
-        let template_string = match &args.template {
-            Some(value) => value.to_string(),
-            None => command.settings().config().get_string("templates.log")?,
+        let template_string = match (&args.template, &args.template_file) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(path)) => read_template_file(path)?,
+            (None, None) => command.settings().config().get_string("templates.log")?,
         };
         template = workspace_command
             .parse_template(
@@ -145,13 +147,7 @@ pub(crate) fn cmd_log(
         let mut formatter = ui.stdout_formatter();
         let formatter = formatter.as_mut();
 
-        if args.deprecated_limit.is_some() {
-            writeln!(
-                ui.warning_default(),
-                "The -l shorthand is deprecated, use -n instead."
-            )?;
-        }
-        let limit = args.limit.or(args.deprecated_limit).unwrap_or(usize::MAX);
+        let limit = args.limit.resolve(ui)?.unwrap_or(usize::MAX);
 
         if !args.no_graph {
             let mut graph = get_graphlog(command.settings(), formatter.raw());
@@ -51,6 +51,10 @@ pub(crate) struct NewArgs {
     /// The change description to use
     #[arg(long = "message", short, value_name = "MESSAGE")]
     message_paragraphs: Vec<String>,
+    /// Allow each revset to resolve to more than one revision, as if it were
+    /// prefixed with `all:`
+    #[arg(long)]
+    all: bool,
     /// Deprecated. Please prefix the revset with `all:` instead.
     #[arg(long, short = 'L', hide = true)]
     allow_large_revsets: bool,
@@ -100,12 +104,12 @@ Please use `jj new 'all:x|y'` instead of `jj new --allow-large-revsets x y`.",
 
     if !args.insert_before.is_empty() && !args.insert_after.is_empty() {
         parent_commits = workspace_command
-            .resolve_some_revsets_default_single(&args.insert_after)?
+            .resolve_some_revsets(&args.insert_after, args.all)?
             .into_iter()
             .collect_vec();
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
         children_commits = workspace_command
-            .resolve_some_revsets_default_single(&args.insert_before)?
+            .resolve_some_revsets(&args.insert_before, args.all)?
             .into_iter()
             .collect_vec();
         let children_commit_ids = children_commits.iter().ids().cloned().collect();
@@ -122,7 +126,7 @@ Please use `jj new 'all:x|y'` instead of `jj new --allow-large-revsets x y`.",
         // The parents of the new commit will be the parents of the target commits
         // which are not descendants of other target commits.
         children_commits = workspace_command
-            .resolve_some_revsets_default_single(&args.insert_before)?
+            .resolve_some_revsets(&args.insert_before, args.all)?
             .into_iter()
             .collect_vec();
         let children_commit_ids = children_commits.iter().ids().cloned().collect();
@@ -147,7 +151,7 @@ Please use `jj new 'all:x|y'` instead of `jj new --allow-large-revsets x y`.",
             .try_collect()?;
     } else if !args.insert_after.is_empty() {
         parent_commits = workspace_command
-            .resolve_some_revsets_default_single(&args.insert_after)?
+            .resolve_some_revsets(&args.insert_after, args.all)?
             .into_iter()
             .collect_vec();
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
@@ -165,7 +169,7 @@ Please use `jj new 'all:x|y'` instead of `jj new --allow-large-revsets x y`.",
             .try_collect()?;
     } else {
         parent_commits = workspace_command
-            .resolve_some_revsets_default_single(&args.revisions)?
+            .resolve_some_revsets(&args.revisions, args.all)?
             .into_iter()
             .collect_vec();
         parent_commit_ids = parent_commits.iter().ids().cloned().collect();
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use itertools::Itertools;
 use jj_lib::commit::Commit;
 use jj_lib::dag_walk::topo_order_reverse_ok;
@@ -20,7 +22,9 @@ use jj_lib::repo::Repo;
 use jj_lib::rewrite::rebase_to_dest_parent;
 use tracing::instrument;
 
-use crate::cli_util::{format_template, CommandHelper, LogContentFormat, RevisionArg};
+use crate::cli_util::{
+    format_template, read_template_file, CommandHelper, LimitArg, LogContentFormat, RevisionArg,
+};
 use crate::command_error::CommandError;
 use crate::commit_templater::CommitTemplateLanguage;
 use crate::diff_util::{DiffFormatArgs, DiffRenderer};
@@ -38,17 +42,8 @@ use crate::ui::Ui;
 pub(crate) struct ObslogArgs {
     #[arg(long, short, default_value = "@")]
     revision: RevisionArg,
-    /// Limit number of revisions to show
-    #[arg(long, short = 'n')]
-    limit: Option<usize>,
-    // TODO: Delete `-l` alias in jj 0.25+
-    #[arg(
-        short = 'l',
-        hide = true,
-        conflicts_with = "limit",
-        value_name = "LIMIT"
-    )]
-    deprecated_limit: Option<usize>,
+    #[command(flatten)]
+    limit: LimitArg,
     /// Don't show the graph, show a flat list of revisions
     #[arg(long)]
     no_graph: bool,
@@ -57,6 +52,14 @@ pub(crate) struct ObslogArgs {
     /// For the syntax, see https://github.com/martinvonz/jj/blob/main/docs/templates.md
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Render each revision using the template read from the given file
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "template",
+        value_hint = clap::ValueHint::FilePath
+    )]
+    template_file: Option<PathBuf>,
     /// Show patch compared to the previous version of this change
     ///
     /// If the previous version has different parents, it will be temporarily
@@ -86,9 +89,10 @@ pub(crate) fn cmd_obslog(
     let node_template;
     {
         let language = workspace_command.commit_template_language()?;
-        let template_string = match &args.template {
-            Some(value) => value.to_string(),
-            None => command.settings().config().get_string("templates.log")?,
+        let template_string = match (&args.template, &args.template_file) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(path)) => read_template_file(path)?,
+            (None, None) => command.settings().config().get_string("templates.log")?,
         };
         template = workspace_command
             .parse_template(
@@ -115,13 +119,7 @@ pub(crate) fn cmd_obslog(
         |commit: &Commit| commit.id().clone(),
         |commit: &Commit| commit.predecessors().collect_vec(),
     )?;
-    if args.deprecated_limit.is_some() {
-        writeln!(
-            ui.warning_default(),
-            "The -l shorthand is deprecated, use -n instead."
-        )?;
-    }
-    if let Some(n) = args.limit.or(args.deprecated_limit) {
+    if let Some(n) = args.limit.resolve(ui)? {
         commits.truncate(n);
     }
     if !args.no_graph {
@@ -101,8 +101,7 @@ pub fn cmd_op_abandon(
     )?;
     let [new_head_id]: [OperationId; 1] = stats.new_head_ids.try_into().unwrap();
     if current_head_op.id() == &new_head_id {
-        writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
+        return command.report_nothing_changed(ui);
     }
     writeln!(
         ui.status(),
@@ -195,6 +195,10 @@ pub(crate) struct RebaseArgs {
     #[arg(long, conflicts_with = "revisions")]
     skip_empty: bool,
 
+    /// Allow each revset to resolve to more than one revision, as if it were
+    /// prefixed with `all:`
+    #[arg(long)]
+    all: bool,
     /// Deprecated. Please prefix the revset with `all:` instead.
     #[arg(long, short = 'L', hide = true)]
     allow_large_revsets: bool,
@@ -243,9 +247,9 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
             .try_collect()?; // in reverse topological order
         if !args.insert_after.is_empty() && !args.insert_before.is_empty() {
             let after_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_after)?;
+                workspace_command.resolve_some_revsets(&args.insert_after, args.all)?;
             let before_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_before)?;
+                workspace_command.resolve_some_revsets(&args.insert_before, args.all)?;
             rebase_revisions_after_before(
                 ui,
                 command.settings(),
@@ -256,7 +260,7 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
             )?;
         } else if !args.insert_after.is_empty() {
             let after_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_after)?;
+                workspace_command.resolve_some_revsets(&args.insert_after, args.all)?;
             rebase_revisions_after(
                 ui,
                 command.settings(),
@@ -266,7 +270,7 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
             )?;
         } else if !args.insert_before.is_empty() {
             let before_commits =
-                workspace_command.resolve_some_revsets_default_single(&args.insert_before)?;
+                workspace_command.resolve_some_revsets(&args.insert_before, args.all)?;
             rebase_revisions_before(
                 ui,
                 command.settings(),
@@ -276,7 +280,7 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
             )?;
         } else {
             let new_parents = workspace_command
-                .resolve_some_revsets_default_single(&args.destination)?
+                .resolve_some_revsets(&args.destination, args.all)?
                 .into_iter()
                 .collect_vec();
             rebase_revisions(
@@ -289,10 +293,10 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
         }
     } else if !args.source.is_empty() {
         let new_parents = workspace_command
-            .resolve_some_revsets_default_single(&args.destination)?
+            .resolve_some_revsets(&args.destination, args.all)?
             .into_iter()
             .collect_vec();
-        let source_commits = workspace_command.resolve_some_revsets_default_single(&args.source)?;
+        let source_commits = workspace_command.resolve_some_revsets(&args.source, args.all)?;
         rebase_descendants_transaction(
             ui,
             command.settings(),
@@ -303,13 +307,13 @@ Please use `jj rebase -d 'all:x|y'` instead of `jj rebase --allow-large-revsets
         )?;
     } else {
         let new_parents = workspace_command
-            .resolve_some_revsets_default_single(&args.destination)?
+            .resolve_some_revsets(&args.destination, args.all)?
             .into_iter()
             .collect_vec();
         let branch_commits = if args.branch.is_empty() {
             IndexSet::from([workspace_command.resolve_single_rev(&RevisionArg::AT)?])
         } else {
-            workspace_command.resolve_some_revsets_default_single(&args.branch)?
+            workspace_command.resolve_some_revsets(&args.branch, args.all)?
         };
         rebase_branch(
             ui,
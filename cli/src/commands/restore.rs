@@ -104,7 +104,7 @@ pub(crate) fn cmd_restore(
     let to_tree = to_commit.tree()?;
     let new_tree_id = restore_tree(&from_tree, &to_tree, matcher.as_ref())?;
     if &new_tree_id == to_commit.tree_id() {
-        writeln!(ui.status(), "Nothing changed.")?;
+        command.report_nothing_changed(ui)?;
     } else {
         let mut tx = workspace_command.start_transaction();
         let mut_repo = tx.mut_repo();
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::PathBuf;
+
 use jj_lib::matchers::EverythingMatcher;
 use tracing::instrument;
 
@@ -34,6 +36,14 @@ pub(crate) struct ShowArgs {
     /// For the syntax, see https://github.com/martinvonz/jj/blob/main/docs/templates.md
     #[arg(long, short = 'T')]
     template: Option<String>,
+    /// Render a revision using the template read from the given file
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "template",
+        value_hint = clap::ValueHint::FilePath
+    )]
+    template_file: Option<PathBuf>,
     #[command(flatten)]
     format: DiffFormatArgs,
 }
@@ -46,11 +56,15 @@ pub(crate) fn cmd_show(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let commit = workspace_command.resolve_single_rev(&args.revision)?;
-    let template_string = match &args.template {
-        Some(value) => value.to_string(),
-        None => command.settings().config().get_string("templates.show")?,
+    let template = if let Some(path) = &args.template_file {
+        workspace_command.parse_commit_template_from_path(path)?
+    } else {
+        let template_string = match &args.template {
+            Some(value) => value.to_string(),
+            None => command.settings().config().get_string("templates.show")?,
+        };
+        workspace_command.parse_commit_template(&template_string)?
     };
-    let template = workspace_command.parse_commit_template(&template_string)?;
     let diff_renderer = workspace_command.diff_renderer_for(&args.format)?;
     ui.request_pager();
     let mut formatter = ui.stdout_formatter();
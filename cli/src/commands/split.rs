@@ -106,8 +106,7 @@ the operation will be aborted.
         diff_selector.select(&base_tree, &end_tree, matcher.as_ref(), Some(&instructions))?;
     if &selected_tree_id == commit.tree_id() && diff_selector.is_interactive() {
         // The user selected everything from the original commit.
-        writeln!(ui.status(), "Nothing changed.")?;
-        return Ok(());
+        return command.report_nothing_changed(ui);
     }
     if selected_tree_id == base_tree.id() {
         // The user selected nothing, so the first commit will be empty.
@@ -129,7 +128,12 @@ the operation will be aborted.
         &base_tree,
         &selected_tree,
     )?;
-    let first_description = edit_description(tx.base_repo(), &first_template, command.settings())?;
+    let first_description = edit_description(
+        tx.base_workspace_helper(),
+        &commit,
+        &first_template,
+        command.settings(),
+    )?;
     let first_commit = tx
         .mut_repo()
         .rewrite_commit(command.settings(), &commit)
@@ -165,7 +169,12 @@ the operation will be aborted.
             second_base_tree,
             &second_tree,
         )?;
-        edit_description(tx.base_repo(), &second_template, command.settings())?
+        edit_description(
+            tx.base_workspace_helper(),
+            &commit,
+            &second_template,
+            command.settings(),
+        )?
     };
     let second_commit = tx
         .mut_repo()
@@ -13,14 +13,13 @@
 // limitations under the License.
 
 use itertools::Itertools;
-use jj_lib::repo::Repo;
 use jj_lib::revset::{RevsetExpression, RevsetFilterPredicate};
 use tracing::instrument;
 
 use crate::cli_util::{print_conflicted_paths, CommandHelper};
-use crate::command_error::CommandError;
+use crate::command_error::{immutable_heads_config_error, CommandError};
 use crate::diff_util::DiffFormat;
-use crate::revset_util;
+use crate::revset_util::{self, UserRevsetEvaluationError};
 use crate::ui::Ui;
 
 /// Show high-level repo status
@@ -47,10 +46,7 @@ pub(crate) fn cmd_status(
 ) -> Result<(), CommandError> {
     let workspace_command = command.workspace_helper(ui)?;
     let repo = workspace_command.repo();
-    let maybe_wc_commit = workspace_command
-        .get_wc_commit_id()
-        .map(|id| repo.store().get_commit(id))
-        .transpose()?;
+    let maybe_wc_commit = workspace_command.wc_commit()?;
     let matcher = workspace_command
         .parse_file_patterns(&args.paths)?
         .to_matcher();
@@ -92,6 +88,9 @@ pub(crate) fn cmd_status(
         }
 
         let wc_revset = RevsetExpression::commit(wc_commit.id().clone());
+        let immutable =
+            revset_util::parse_immutable_expression(&workspace_command.revset_parse_context())
+                .map_err(immutable_heads_config_error)?;
         // Ancestors with conflicts, excluding the current working copy commit.
         let ancestors_conflicts = workspace_command
             .attach_revset_evaluator(
@@ -99,11 +98,14 @@ pub(crate) fn cmd_status(
                     .parents()
                     .ancestors()
                     .filtered(RevsetFilterPredicate::HasConflict)
-                    .minus(&revset_util::parse_immutable_expression(
-                        &workspace_command.revset_parse_context(),
-                    )?),
+                    .minus(&immutable),
             )?
-            .evaluate_to_commit_ids()?
+            .evaluate_to_commit_ids()
+            .map_err(|err| match err {
+                // Only the immutable_heads() part can contain symbols.
+                UserRevsetEvaluationError::Resolution(_) => immutable_heads_config_error(err),
+                UserRevsetEvaluationError::Evaluation(_) => err.into(),
+            })?
             .collect();
         workspace_command.report_repo_conflicts(formatter, repo, ancestors_conflicts)?;
     } else {
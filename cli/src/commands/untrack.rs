@@ -72,6 +72,7 @@ pub(crate) fn cmd_untrack(
         fsmonitor_settings: command.settings().fsmonitor_settings()?,
         progress: None,
         max_new_file_size: command.settings().max_new_file_size()?,
+        parallelism: command.settings().snapshot_parallelism()?,
     })?;
     if wc_tree_id != *new_commit.tree_id() {
         let wc_tree = store.get_root_tree(&wc_tree_id)?;
@@ -105,6 +106,7 @@ Make sure they're ignored, then try again.",
         writeln!(ui.status(), "Rebased {num_rebased} descendant commits")?;
     }
     let repo = tx.commit("untrack paths");
+    command.record_committed_operation(repo.op_id());
     locked_ws.finish(repo.op_id().clone())?;
     Ok(())
 }
@@ -23,15 +23,14 @@ use jj_lib::commit::CommitIteratorExt;
 use jj_lib::file_util;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_store::{OpStoreError, WorkspaceId};
-use jj_lib::operation::Operation;
 use jj_lib::repo::{ReadonlyRepo, Repo};
 use jj_lib::rewrite::merge_commit_trees;
 use jj_lib::workspace::Workspace;
 use tracing::instrument;
 
 use crate::cli_util::{
-    check_stale_working_copy, print_checkout_stats, short_commit_hash, CommandHelper, RevisionArg,
-    WorkingCopyFreshness, WorkspaceCommandHelper,
+    check_stale_working_copy, print_checkout_stats, read_working_copy_operation, short_commit_hash,
+    CommandHelper, RevisionArg, WorkingCopyFreshness, WorkspaceCommandHelper,
 };
 use crate::command_error::{internal_error_with_message, user_error, CommandError};
 use crate::ui::Ui;
@@ -165,6 +164,7 @@ fn cmd_workspace_add(
         working_copy_factory,
         workspace_id,
     )?;
+    command.record_committed_operation(repo.op_id());
     writeln!(
         ui.status(),
         "Created workspace in \"{}\"",
@@ -329,6 +329,7 @@ fn create_and_check_out_recovery_commit(
         .write()?;
     mut_repo.set_wc_commit(workspace_id, new_commit.id().clone())?;
     let repo = tx.commit("recovery commit");
+    command.record_committed_operation(repo.op_id());
 
     locked_workspace.locked_wc().recover(&new_commit)?;
     locked_workspace.finish(repo.op_id().clone())?;
@@ -348,28 +349,17 @@ fn for_stale_working_copy(
     command: &CommandHelper,
 ) -> Result<(WorkspaceCommandHelper, bool), CommandError> {
     let workspace = command.load_workspace()?;
-    let op_store = workspace.repo_loader().op_store();
-    let (repo, recovered) = {
-        let op_id = workspace.working_copy().operation_id();
-        match op_store.read_operation(op_id) {
-            Ok(op_data) => (
-                workspace.repo_loader().load_at(&Operation::new(
-                    op_store.clone(),
-                    op_id.clone(),
-                    op_data,
-                ))?,
-                false,
-            ),
-            Err(e @ OpStoreError::ObjectNotFound { .. }) => {
-                writeln!(
-                    ui.status(),
-                    "Failed to read working copy's current operation; attempting recovery. Error \
-                     message from read attempt: {e}"
-                )?;
-                (create_and_check_out_recovery_commit(ui, command)?, true)
-            }
-            Err(e) => return Err(e.into()),
+    let (repo, recovered) = match read_working_copy_operation(&workspace) {
+        Ok(op) => (workspace.repo_loader().load_at(&op)?, false),
+        Err(e @ OpStoreError::ObjectNotFound { .. }) => {
+            writeln!(
+                ui.status(),
+                "Failed to read working copy's current operation; attempting recovery. Error \
+                 message from read attempt: {e}"
+            )?;
+            (create_and_check_out_recovery_commit(ui, command)?, true)
         }
+        Err(e) => return Err(e.into()),
     };
     Ok((command.for_loaded_repo(ui, workspace, repo)?, recovered))
 }
@@ -395,8 +385,7 @@ fn cmd_workspace_update_stale(
             return Ok(());
         }
 
-        let wc_commit_id = workspace_command.get_wc_commit_id().unwrap();
-        workspace_command.repo().store().get_commit(wc_commit_id)?
+        workspace_command.wc_commit()?.unwrap()
     };
     let mut workspace_command = command.workspace_helper_no_snapshot(ui)?;
 
@@ -436,7 +425,7 @@ fn cmd_workspace_update_stale(
                 })?;
                 writeln!(formatter)?;
             }
-            print_checkout_stats(ui, stats, &desired_wc_commit)?;
+            print_checkout_stats(ui, &workspace_command, stats, &desired_wc_commit)?;
         }
     }
     Ok(())
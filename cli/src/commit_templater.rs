@@ -23,7 +23,7 @@ use jj_lib::backend::{ChangeId, CommitId};
 use jj_lib::commit::Commit;
 use jj_lib::extensions_map::ExtensionsMap;
 use jj_lib::git;
-use jj_lib::hex_util::to_reverse_hex;
+use jj_lib::hex_util::encode_reverse_hex;
 use jj_lib::id_prefix::IdPrefixContext;
 use jj_lib::object_id::ObjectId as _;
 use jj_lib::op_store::{RefTarget, RemoteRef, WorkspaceId};
@@ -697,10 +697,20 @@ fn evaluate_immutable_revset<'repo>(
     // optimize "::<recent_heads>" query to use bitset-based implementation.
     let expression = revset_util::parse_immutable_expression(&language.revset_parse_context)
         .map_err(|err| {
-            TemplateParseError::expression("Failed to parse revset", span).with_source(err)
+            TemplateParseError::expression("Invalid `revset-aliases.immutable_heads()`", span)
+                .with_source(err)
         })?;
-
-    evaluate_revset_expression(language, span, expression)
+    let symbol_resolver = revset_util::default_symbol_resolver(
+        language.repo,
+        language.revset_parse_context.symbol_resolvers(),
+        language.id_prefix_context,
+    );
+    let revset =
+        revset_util::evaluate(language.repo, &symbol_resolver, expression).map_err(|err| {
+            TemplateParseError::expression("Invalid `revset-aliases.immutable_heads()`", span)
+                .with_source(err)
+        })?;
+    Ok(revset)
 }
 
 fn evaluate_user_revset<'repo>(
@@ -1083,11 +1093,7 @@ impl CommitOrChangeId {
     pub fn hex(&self) -> String {
         match self {
             CommitOrChangeId::Commit(id) => id.hex(),
-            CommitOrChangeId::Change(id) => {
-                // TODO: We can avoid the unwrap() and make this more efficient by converting
-                // straight from bytes.
-                to_reverse_hex(&id.hex()).unwrap()
-            }
+            CommitOrChangeId::Change(id) => encode_reverse_hex(id.as_bytes()),
         }
     }
 
@@ -64,6 +64,33 @@ pub enum ConfigError {
     ConfigCreateError(#[from] std::io::Error),
 }
 
+/// Top-level config tables and keys that may be used by jj.
+const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
+    "aliases",
+    "colors",
+    "core",
+    "debug",
+    "diff",
+    "experimental-advance-branches",
+    "fix",
+    "format",
+    "git",
+    "hooks",
+    "merge-tools",
+    "messages",
+    "operation",
+    "revset-aliases",
+    "revsets",
+    "signing",
+    "snapshot",
+    "template-aliases",
+    "templates",
+    "ui",
+    "user",
+    "working-copy",
+    "workspace",
+];
+
 /// Dotted config name path.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct ConfigNamePathBuf(Vec<toml_edit::Key>);
@@ -204,18 +231,27 @@ pub struct LayeredConfigs {
     user: Option<config::Config>,
     repo: Option<config::Config>,
     env_overrides: config::Config,
+    file_overrides: Option<config::Config>,
     arg_overrides: Option<config::Config>,
 }
 
 impl LayeredConfigs {
     /// Initializes configs with infallible sources.
     pub fn from_environment(default: config::Config) -> Self {
+        Self::from_environment_with_prefix(default, "JJ")
+    }
+
+    /// Initializes configs with infallible sources. Override environment
+    /// variables are read with the given prefix instead of `JJ`, e.g.
+    /// `<env_prefix>_USER`.
+    pub fn from_environment_with_prefix(default: config::Config, env_prefix: &str) -> Self {
         LayeredConfigs {
             default,
             env_base: env_base(),
             user: None,
             repo: None,
-            env_overrides: env_overrides(),
+            env_overrides: env_overrides(env_prefix),
+            file_overrides: None,
             arg_overrides: None,
         }
     }
@@ -242,6 +278,22 @@ impl LayeredConfigs {
         repo_path.join("config.toml")
     }
 
+    /// Reads the `--config-file` arguments. Later files take precedence.
+    pub fn read_config_file_args(&mut self, paths: &[PathBuf]) -> Result<(), ConfigError> {
+        let config = paths
+            .iter()
+            .fold(config::Config::builder(), |builder, path| {
+                builder.add_source(
+                    config::File::from(path.as_path())
+                        .required(true)
+                        .format(config::FileFormat::Toml),
+                )
+            })
+            .build()?;
+        self.file_overrides = Some(config);
+        Ok(())
+    }
+
     pub fn parse_config_args(&mut self, toml_strs: &[String]) -> Result<(), ConfigError> {
         let config = toml_strs
             .iter()
@@ -253,6 +305,24 @@ impl LayeredConfigs {
         Ok(())
     }
 
+    /// Returns top-level keys of the `--config-toml` arguments which are
+    /// neither known to jj nor defined by the default config.
+    pub fn unknown_arg_override_keys(&self) -> Result<Vec<String>, ConfigError> {
+        let Some(arg_overrides) = &self.arg_overrides else {
+            return Ok(vec![]);
+        };
+        let default_table = self.default.collect()?;
+        let keys = arg_overrides
+            .collect()?
+            .into_keys()
+            .filter(|key| {
+                !KNOWN_TOP_LEVEL_KEYS.contains(&key.as_str()) && !default_table.contains_key(key)
+            })
+            .sorted()
+            .collect();
+        Ok(keys)
+    }
+
     /// Creates new merged config.
     pub fn merge(&self) -> config::Config {
         self.sources()
@@ -265,6 +335,47 @@ impl LayeredConfigs {
             .expect("loaded configs should be merged without error")
     }
 
+    /// Creates new merged config with the given TOML layered on top of all
+    /// the other layers. `self` isn't modified.
+    pub fn merge_with_toml(&self, toml_str: &str) -> Result<config::Config, ConfigError> {
+        let config = config::Config::builder()
+            .add_source(self.merge())
+            .add_source(config::File::from_str(toml_str, config::FileFormat::Toml))
+            .build()?;
+        Ok(config)
+    }
+
+    /// Renames `old_key` to `new_key` in each config layer.
+    ///
+    /// If a layer sets both keys, the value of `new_key` takes precedence.
+    /// Returns true if `old_key` was set in any layer.
+    pub fn migrate_key(&mut self, old_key: &str, new_key: &str) -> Result<bool, ConfigError> {
+        let mut found = false;
+        let configs = [
+            Some(&mut self.default),
+            Some(&mut self.env_base),
+            self.user.as_mut(),
+            self.repo.as_mut(),
+            Some(&mut self.env_overrides),
+            self.file_overrides.as_mut(),
+            self.arg_overrides.as_mut(),
+        ];
+        for config in configs.into_iter().flatten() {
+            let Some(value) = config.get::<config::Value>(old_key).optional()? else {
+                continue;
+            };
+            found = true;
+            if config.get::<config::Value>(new_key).optional()?.is_some() {
+                continue;
+            }
+            *config = config::Config::builder()
+                .add_source(config.clone())
+                .set_override(new_key, value)?
+                .build()?;
+        }
+        Ok(found)
+    }
+
     pub fn sources(&self) -> Vec<(ConfigSource, &config::Config)> {
         let config_sources = [
             (ConfigSource::Default, Some(&self.default)),
@@ -272,6 +383,7 @@ impl LayeredConfigs {
             (ConfigSource::User, self.user.as_ref()),
             (ConfigSource::Repo, self.repo.as_ref()),
             (ConfigSource::Env, Some(&self.env_overrides)),
+            (ConfigSource::CommandArg, self.file_overrides.as_ref()),
             (ConfigSource::CommandArg, self.arg_overrides.as_ref()),
         ];
         config_sources
@@ -448,7 +560,13 @@ pub fn new_config_path() -> Result<Option<PathBuf>, ConfigError> {
 /// Environment variables that should be overridden by config values
 fn env_base() -> config::Config {
     let mut builder = config::Config::builder();
-    if env::var("NO_COLOR").is_ok() {
+    let clicolor_force =
+        env::var_os("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if clicolor_force {
+        // https://bixense.com/clicolors/
+        builder = builder.set_override("ui.color", "always").unwrap();
+    } else if no_color {
         // "User-level configuration files and per-instance command-line arguments
         // should override $NO_COLOR." https://no-color.org/
         builder = builder.set_override("ui.color", "never").unwrap();
@@ -489,36 +607,37 @@ pub fn default_config() -> config::Config {
 }
 
 /// Environment variables that override config values
-fn env_overrides() -> config::Config {
+fn env_overrides(env_prefix: &str) -> config::Config {
     let mut builder = config::Config::builder();
-    if let Ok(value) = env::var("JJ_USER") {
+    let var = |name: &str| env::var(format!("{env_prefix}_{name}"));
+    if let Ok(value) = var("USER") {
         builder = builder.set_override("user.name", value).unwrap();
     }
-    if let Ok(value) = env::var("JJ_EMAIL") {
+    if let Ok(value) = var("EMAIL") {
         builder = builder.set_override("user.email", value).unwrap();
     }
-    if let Ok(value) = env::var("JJ_TIMESTAMP") {
+    if let Ok(value) = var("TIMESTAMP") {
         builder = builder
             .set_override("debug.commit-timestamp", value)
             .unwrap();
     }
-    if let Ok(value) = env::var("JJ_RANDOMNESS_SEED") {
+    if let Ok(value) = var("RANDOMNESS_SEED") {
         builder = builder
             .set_override("debug.randomness-seed", value)
             .unwrap();
     }
-    if let Ok(value) = env::var("JJ_OP_TIMESTAMP") {
+    if let Ok(value) = var("OP_TIMESTAMP") {
         builder = builder
             .set_override("debug.operation-timestamp", value)
             .unwrap();
     }
-    if let Ok(value) = env::var("JJ_OP_HOSTNAME") {
+    if let Ok(value) = var("OP_HOSTNAME") {
         builder = builder.set_override("operation.hostname", value).unwrap();
     }
-    if let Ok(value) = env::var("JJ_OP_USERNAME") {
+    if let Ok(value) = var("OP_USERNAME") {
         builder = builder.set_override("operation.username", value).unwrap();
     }
-    if let Ok(value) = env::var("JJ_EDITOR") {
+    if let Ok(value) = var("EDITOR") {
         builder = builder.set_override("ui.editor", value).unwrap();
     }
     builder.build().unwrap()
@@ -568,6 +687,15 @@ pub fn write_config_value_to_file(
     key: &ConfigNamePathBuf,
     value_str: &str,
     path: &Path,
+) -> Result<(), CommandError> {
+    write_config_values_to_file(&[(key, value_str)], path)
+}
+
+/// Sets all `values` in the config file at `path`, which is read and written
+/// only once. Nothing is written if any of the values can't be set.
+pub fn write_config_values_to_file(
+    values: &[(&ConfigNamePathBuf, &str)],
+    path: &Path,
 ) -> Result<(), CommandError> {
     // Read config
     let config_toml = std::fs::read_to_string(path).or_else(|err| {
@@ -587,7 +715,25 @@ pub fn write_config_value_to_file(
         )
     })?;
 
-    // Apply config value
+    // Apply config values
+    for &(key, value_str) in values {
+        set_config_value_in_document(&mut doc, key, value_str)?;
+    }
+
+    // Write config back
+    std::fs::write(path, doc.to_string()).map_err(|err| {
+        user_error_with_message(
+            format!("Failed to write file {path}", path = path.display()),
+            err,
+        )
+    })
+}
+
+fn set_config_value_in_document(
+    doc: &mut toml_edit::Document,
+    key: &ConfigNamePathBuf,
+    value_str: &str,
+) -> Result<(), CommandError> {
     // Interpret value as string if it can't be parsed as a TOML value.
     // TODO(#531): Infer types based on schema (w/ --type arg to override).
     let item = match value_str.parse() {
@@ -619,14 +765,7 @@ pub fn write_config_value_to_file(
         }
     }
     target_table[last_key_part] = item;
-
-    // Write config back
-    std::fs::write(path, doc.to_string()).map_err(|err| {
-        user_error_with_message(
-            format!("Failed to write file {path}", path = path.display()),
-            err,
-        )
-    })
+    Ok(())
 }
 
 /// Command name and arguments specified by config.
@@ -872,6 +1011,99 @@ mod tests {
         assert_eq!(args, ["-nw"].as_ref());
     }
 
+    #[test]
+    fn test_layered_configs_merge_with_toml() {
+        let empty_config = config::Config::default();
+        let arg_config = config::Config::builder()
+            .set_override("ui.color", "always")
+            .unwrap()
+            .set_override("ui.pager", "less")
+            .unwrap()
+            .build()
+            .unwrap();
+        let layered_configs = LayeredConfigs {
+            default: empty_config.to_owned(),
+            env_base: empty_config.to_owned(),
+            user: None,
+            repo: None,
+            env_overrides: empty_config,
+            file_overrides: None,
+            arg_overrides: Some(arg_config),
+        };
+
+        // The extra layer takes precedence even over --config-toml.
+        let config = layered_configs
+            .merge_with_toml(r#"ui.color = "never""#)
+            .unwrap();
+        assert_eq!(config.get_string("ui.color").unwrap(), "never");
+        assert_eq!(config.get_string("ui.pager").unwrap(), "less");
+        assert_eq!(
+            layered_configs.merge().get_string("ui.color").unwrap(),
+            "always"
+        );
+
+        assert!(layered_configs.merge_with_toml("ui.color =").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides_prefix() {
+        std::env::set_var("JJ_TEST_ENV_PREFIX_USER", "Test User");
+        let config = env_overrides("JJ_TEST_ENV_PREFIX");
+        assert_eq!(config.get_string("user.name").unwrap(), "Test User");
+        let config = env_overrides("JJ_TEST_ENV_PREFIX_OTHER");
+        assert!(config.get_string("user.name").is_err());
+    }
+
+    #[test]
+    fn test_layered_configs_migrate_key() {
+        let empty_config = config::Config::default();
+        let default_config = config::Config::builder()
+            .set_override("ui.new-key", "default")
+            .unwrap()
+            .build()
+            .unwrap();
+        let user_config = config::Config::builder()
+            .set_override("ui.old-key", "user")
+            .unwrap()
+            .build()
+            .unwrap();
+        let repo_config = config::Config::builder()
+            .set_override("ui.old-key", "repo-old")
+            .unwrap()
+            .set_override("ui.new-key", "repo-new")
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut layered_configs = LayeredConfigs {
+            default: default_config,
+            env_base: empty_config.to_owned(),
+            user: Some(user_config),
+            repo: None,
+            env_overrides: empty_config,
+            file_overrides: None,
+            arg_overrides: None,
+        };
+
+        // The old key overrides the new key set in lower layers.
+        assert!(layered_configs
+            .migrate_key("ui.old-key", "ui.new-key")
+            .unwrap());
+        let config = layered_configs.merge();
+        assert_eq!(config.get_string("ui.new-key").unwrap(), "user");
+
+        // The new key takes precedence within the same layer.
+        layered_configs.repo = Some(repo_config);
+        assert!(layered_configs
+            .migrate_key("ui.old-key", "ui.new-key")
+            .unwrap());
+        let config = layered_configs.merge();
+        assert_eq!(config.get_string("ui.new-key").unwrap(), "repo-new");
+
+        assert!(!layered_configs
+            .migrate_key("ui.unset", "ui.new-key")
+            .unwrap());
+    }
+
     #[test]
     fn test_layered_configs_resolved_config_values_empty() {
         let empty_config = config::Config::default();
@@ -881,6 +1113,7 @@ mod tests {
             user: None,
             repo: None,
             env_overrides: empty_config,
+            file_overrides: None,
             arg_overrides: None,
         };
         assert_eq!(
@@ -912,6 +1145,7 @@ mod tests {
             user: None,
             repo: Some(repo_config),
             env_overrides: empty_config,
+            file_overrides: None,
             arg_overrides: None,
         };
         // Note: "email" is alphabetized, before "name" from same layer.
@@ -1035,6 +1269,7 @@ mod tests {
             user: Some(user_config),
             repo: Some(repo_config),
             env_overrides: empty_config,
+            file_overrides: None,
             arg_overrides: None,
         };
         insta::assert_debug_snapshot!(
@@ -1108,6 +1343,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_write_config_values_to_file() {
+        let temp_dir = testutils::new_temp_dir();
+        let path = temp_dir.path().join("config.toml");
+        std::fs::write(&path, "[ui]\ncolor = \"never\"\n").unwrap();
+        let key1 = ConfigNamePathBuf::from_iter(["ui", "color"]);
+        let key2 = ConfigNamePathBuf::from_iter(["user", "name"]);
+        write_config_values_to_file(&[(&key1, "always"), (&key2, "Test User")], &path).unwrap();
+        insta::assert_snapshot!(std::fs::read_to_string(&path).unwrap(), @r###"
+        [ui]
+        color = "always"
+
+        [user]
+        name = "Test User"
+        "###);
+
+        // Nothing is written if any of the values can't be set
+        let key3 = ConfigNamePathBuf::from_iter(["ui"]);
+        let result = write_config_values_to_file(&[(&key1, "auto"), (&key3, "x")], &path);
+        assert!(result.is_err());
+        insta::assert_snapshot!(std::fs::read_to_string(&path).unwrap(), @r###"
+        [ui]
+        color = "always"
+
+        [user]
+        name = "Test User"
+        "###);
+    }
+
     #[test]
     fn test_config_path_home_dir_existing() -> anyhow::Result<()> {
         TestCase {
@@ -2,8 +2,7 @@ use itertools::Itertools;
 use jj_lib::commit::Commit;
 use jj_lib::matchers::EverythingMatcher;
 use jj_lib::merged_tree::MergedTree;
-use jj_lib::repo::ReadonlyRepo;
-use jj_lib::settings::UserSettings;
+use jj_lib::settings::{ConfigResultExt as _, UserSettings};
 
 use crate::cli_util::{edit_temp_file, WorkspaceCommandHelper};
 use crate::command_error::CommandError;
@@ -12,30 +11,54 @@ use crate::formatter::PlainTextFormatter;
 use crate::text_util;
 use crate::ui::Ui;
 
+/// Returns the prefix of the lines to be removed from the edited description.
+pub fn editor_comment_prefix(settings: &UserSettings) -> Result<String, CommandError> {
+    Ok(settings.config().get_string("ui.editor-comment-prefix")?)
+}
+
+/// Opens the editor to edit the `description` of the `commit`.
+///
+/// The `templates.draft_commit_description` template, if configured, is
+/// rendered for the `commit` and appended in place of the default
+/// instructions. Lines starting with the `ui.editor-comment-prefix` are
+/// removed from the result.
 pub fn edit_description(
-    repo: &ReadonlyRepo,
+    workspace_command: &WorkspaceCommandHelper,
+    commit: &Commit,
     description: &str,
     settings: &UserSettings,
 ) -> Result<String, CommandError> {
-    let description = format!(
-        r#"{}
-JJ: Lines starting with "JJ: " (like this one) will be removed.
-"#,
-        description
-    );
+    let prefix = editor_comment_prefix(settings)?;
+    let footer = match settings
+        .config()
+        .get_string("templates.draft_commit_description")
+        .optional()?
+    {
+        Some(template_text) => {
+            let template = workspace_command.parse_commit_template(&template_text)?;
+            let mut output = Vec::new();
+            template.format(commit, &mut PlainTextFormatter::new(&mut output))?;
+            text_util::complete_newline(String::from_utf8_lossy(&output))
+        }
+        None => {
+            format!("{prefix} Lines starting with \"{prefix} \" (like this one) will be removed.\n")
+        }
+    };
+    let description = format!("{description}\n{footer}");
 
     let description = edit_temp_file(
         "description",
         ".jjdescription",
-        repo.repo_path(),
+        workspace_command.repo().repo_path(),
         &description,
         settings,
     )?;
 
     // Normalize line ending, remove leading and trailing blank lines.
+    let comment_prefix = format!("{prefix} ");
     let description = description
         .lines()
-        .filter(|line| !line.starts_with("JJ: "))
+        .filter(|line| !line.starts_with(&comment_prefix))
         .join("\n");
     Ok(text_util::complete_newline(description.trim_matches('\n')))
 }
@@ -44,7 +67,7 @@ JJ: Lines starting with "JJ: " (like this one) will be removed.
 /// then that one is used. Otherwise we concatenate the messages and ask the
 /// user to edit the result in their editor.
 pub fn combine_messages(
-    repo: &ReadonlyRepo,
+    workspace_command: &WorkspaceCommandHelper,
     sources: &[&Commit],
     destination: &Commit,
     settings: &UserSettings,
@@ -67,14 +90,17 @@ pub fn combine_messages(
     // Produce a combined description with instructions for the user to edit.
     // Include empty descriptins too, so the user doesn't have to wonder why they
     // only see 2 descriptions when they combined 3 commits.
-    let mut combined = "JJ: Enter a description for the combined commit.".to_string();
-    combined.push_str("\nJJ: Description from the destination commit:\n");
+    let prefix = editor_comment_prefix(settings)?;
+    let mut combined = format!("{prefix} Enter a description for the combined commit.");
+    combined.push_str(&format!(
+        "\n{prefix} Description from the destination commit:\n"
+    ));
     combined.push_str(destination.description());
     for commit in sources {
-        combined.push_str("\nJJ: Description from source commit:\n");
+        combined.push_str(&format!("\n{prefix} Description from source commit:\n"));
         combined.push_str(commit.description());
     }
-    edit_description(repo, &combined, settings)
+    edit_description(workspace_command, destination, &combined, settings)
 }
 
 /// Create a description from a list of paragraphs.
@@ -112,7 +138,8 @@ pub fn description_template_for_describe(
     if diff_summary_bytes.is_empty() {
         Ok(description)
     } else {
-        Ok(description + "\n" + &diff_summary_to_description(&diff_summary_bytes))
+        let prefix = editor_comment_prefix(settings)?;
+        Ok(description + "\n" + &diff_summary_to_description(&prefix, &diff_summary_bytes))
     }
 }
 
@@ -134,9 +161,10 @@ pub fn description_template_for_commit(
         to_tree,
         &EverythingMatcher,
     )?;
+    let prefix = editor_comment_prefix(settings)?;
     let mut template_chunks = Vec::new();
     if !intro.is_empty() {
-        template_chunks.push(format!("JJ: {intro}\n"));
+        template_chunks.push(format!("{prefix} {intro}\n"));
     }
     template_chunks.push(if overall_commit_description.is_empty() {
         settings.default_description()
@@ -145,16 +173,16 @@ pub fn description_template_for_commit(
     });
     if !diff_summary_bytes.is_empty() {
         template_chunks.push("\n".to_owned());
-        template_chunks.push(diff_summary_to_description(&diff_summary_bytes));
+        template_chunks.push(diff_summary_to_description(&prefix, &diff_summary_bytes));
     }
     Ok(template_chunks.concat())
 }
 
-pub fn diff_summary_to_description(bytes: &[u8]) -> String {
+pub fn diff_summary_to_description(prefix: &str, bytes: &[u8]) -> String {
     let text = std::str::from_utf8(bytes).expect(
         "Summary diffs and repo paths must always be valid UTF8.",
         // Double-check this assumption for diffs that include file content.
     );
-    "JJ: This commit contains the following changes:\n".to_owned()
-        + &textwrap::indent(text, "JJ:     ")
+    format!("{prefix} This commit contains the following changes:\n")
+        + &textwrap::indent(text, &format!("{prefix}     "))
 }
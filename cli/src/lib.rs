@@ -30,6 +30,7 @@ pub mod merge_tools;
 pub mod operation_templater;
 mod progress;
 pub mod revset_util;
+mod span_timing;
 pub mod template_builder;
 pub mod template_parser;
 pub mod templater;
@@ -282,6 +282,7 @@ diff editing in mind and be a little inaccurate.
             fsmonitor_settings: FsmonitorSettings::None,
             progress: None,
             max_new_file_size: u64::MAX,
+            parallelism: None,
         })?;
         Ok(output_tree_state.current_tree_id().clone())
     }
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::io::{self, Write};
-use std::process::{Command, ExitStatus, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use jj_lib::backend::{FileId, MergedTreeId, TreeValue};
@@ -48,6 +50,9 @@ pub struct ExternalMergeTool {
     // TODO: Instead of a boolean, this could denote the flavor of conflict markers to put in
     // the file (`jj` or `diff3` for example).
     pub merge_tool_edits_conflict_markers: bool,
+    /// If set, the merge tool is killed if it doesn't exit within this many
+    /// seconds when resolving conflicts.
+    pub timeout: Option<u64>,
 }
 
 impl Default for ExternalMergeTool {
@@ -63,6 +68,7 @@ impl Default for ExternalMergeTool {
             edit_args: ["$left", "$right"].map(ToOwned::to_owned).to_vec(),
             merge_args: vec![],
             merge_tool_edits_conflict_markers: false,
+            timeout: None,
         }
     }
 }
@@ -117,6 +123,12 @@ pub enum ExternalToolError {
     },
     #[error("Tool exited with {exit_status} (run with --debug to see the exact invocation)")]
     ToolAborted { exit_status: ExitStatus },
+    #[error(
+        "Tool didn't exit within {} seconds and was killed (run with --debug to see the exact \
+         invocation)",
+        timeout.as_secs()
+    )]
+    TimedOut { timeout: Duration },
     #[error("I/O error")]
     Io(#[source] std::io::Error),
 }
@@ -174,12 +186,13 @@ pub fn run_mergetool_external(
     let mut cmd = Command::new(&editor.program);
     cmd.args(interpolate_variables(&editor.merge_args, &paths));
     tracing::info!(?cmd, "Invoking the external merge tool:");
-    let exit_status = cmd
-        .status()
+    let mut child = cmd
+        .spawn()
         .map_err(|e| ExternalToolError::FailedToExecute {
             tool_binary: editor.program.clone(),
             source: e,
         })?;
+    let exit_status = wait_with_timeout(&mut child, editor.timeout.map(Duration::from_secs))?;
     if !exit_status.success() {
         return Err(ConflictResolveError::from(ExternalToolError::ToolAborted {
             exit_status,
@@ -219,6 +232,30 @@ pub fn run_mergetool_external(
     Ok(new_tree)
 }
 
+/// Waits for the `child` process to exit. If it doesn't exit within `timeout`,
+/// it is killed.
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+) -> Result<ExitStatus, ExternalToolError> {
+    let Some(timeout) = timeout else {
+        return child.wait().map_err(ExternalToolError::Io);
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(exit_status) = child.try_wait().map_err(ExternalToolError::Io)? {
+            return Ok(exit_status);
+        }
+        if Instant::now() >= deadline {
+            // The process may have exited in the meantime, so ignore errors.
+            child.kill().ok();
+            child.wait().map_err(ExternalToolError::Io)?;
+            return Err(ExternalToolError::TimedOut { timeout });
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
 pub fn edit_diff_external(
     editor: &ExternalMergeTool,
     left_tree: &MergedTree,
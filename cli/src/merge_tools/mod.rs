@@ -357,6 +357,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -383,6 +384,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -416,6 +418,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -438,6 +441,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -459,6 +463,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -486,6 +491,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -511,6 +517,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -530,6 +537,7 @@ mod tests {
                 ],
                 merge_args: [],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -580,6 +588,7 @@ mod tests {
                     "$output",
                 ],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -625,6 +634,7 @@ mod tests {
                     "$output",
                 ],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -652,6 +662,7 @@ mod tests {
                     "$output",
                 ],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
@@ -682,6 +693,7 @@ mod tests {
                     "$output",
                 ],
                 merge_tool_edits_conflict_markers: false,
+                timeout: None,
             },
         )
         "###);
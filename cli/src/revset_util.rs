@@ -30,7 +30,7 @@ use jj_lib::revset::{
 use jj_lib::settings::ConfigResultExt as _;
 use thiserror::Error;
 
-use crate::command_error::{user_error, CommandError};
+use crate::command_error::{config_error_with_message, user_error, CommandError};
 use crate::config::LayeredConfigs;
 use crate::formatter::Formatter;
 use crate::templater::TemplateRenderer;
@@ -38,6 +38,26 @@ use crate::ui::Ui;
 
 const BUILTIN_IMMUTABLE_HEADS: &str = "immutable_heads";
 
+/// How to resolve a single revision that matches multiple commits sharing one
+/// change id.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all(deserialize = "kebab-case"))]
+pub enum DivergentResolution {
+    /// Report the revision as ambiguous.
+    #[default]
+    Error,
+    /// Pick the most recently created commit.
+    Latest,
+}
+
+pub fn divergent_resolution_setting(
+    config: &config::Config,
+) -> Result<DivergentResolution, CommandError> {
+    config
+        .get::<DivergentResolution>("revsets.resolve-divergent")
+        .map_err(|err| config_error_with_message("Invalid `revsets.resolve-divergent`", err))
+}
+
 #[derive(Debug, Error)]
 pub enum UserRevsetEvaluationError {
     #[error(transparent)]
@@ -142,7 +162,9 @@ pub fn evaluate<'a>(
     symbol_resolver: &DefaultSymbolResolver,
     expression: Rc<RevsetExpression>,
 ) -> Result<Box<dyn Revset + 'a>, UserRevsetEvaluationError> {
-    let resolved = revset::optimize(expression)
+    let expression = revset::optimize(expression);
+    tracing::debug!(?expression, "evaluating revset");
+    let resolved = expression
         .resolve_user_expression(repo, symbol_resolver)
         .map_err(UserRevsetEvaluationError::Resolution)?;
     resolved
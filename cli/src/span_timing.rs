@@ -0,0 +1,109 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracing layer that accumulates the time spent in each span.
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use itertools::Itertools as _;
+use tracing::span;
+use tracing::subscriber::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Total time spent in spans of the same name.
+#[derive(Clone, Copy, Debug, Default)]
+struct SpanTiming {
+    total: Duration,
+    count: usize,
+}
+
+type SpanTimings = HashMap<(&'static str, &'static str), SpanTiming>;
+
+/// Layer that records the time spent in each span, keyed by the span's target
+/// and name. Time spent in nested spans is included in the parent span.
+pub struct SpanTimingLayer {
+    timings: Arc<Mutex<SpanTimings>>,
+}
+
+/// Handle to print the timings recorded by `SpanTimingLayer`.
+#[derive(Clone, Debug)]
+pub struct SpanTimingReport {
+    timings: Arc<Mutex<SpanTimings>>,
+}
+
+/// Time at which the span was last entered.
+struct EnteredAt(Instant);
+
+pub fn new_layer() -> (SpanTimingLayer, SpanTimingReport) {
+    let timings = Arc::new(Mutex::new(HashMap::new()));
+    let layer = SpanTimingLayer {
+        timings: timings.clone(),
+    };
+    (layer, SpanTimingReport { timings })
+}
+
+impl<S> Layer<S> for SpanTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(EnteredAt(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(EnteredAt(entered_at)) = span.extensions_mut().remove::<EnteredAt>() else {
+            return;
+        };
+        let metadata = span.metadata();
+        let mut timings = self.timings.lock().unwrap();
+        let timing = timings
+            .entry((metadata.target(), metadata.name()))
+            .or_default();
+        timing.total += entered_at.elapsed();
+        timing.count += 1;
+    }
+}
+
+impl SpanTimingReport {
+    /// Writes a table of the recorded spans, the slowest first.
+    pub fn write(&self, output: &mut dyn io::Write) -> io::Result<()> {
+        let timings = self.timings.lock().unwrap();
+        if timings.is_empty() {
+            return Ok(());
+        }
+        writeln!(output, "Time spent in spans:")?;
+        let sorted = timings
+            .iter()
+            .sorted_by(|(key_a, a), (key_b, b)| b.total.cmp(&a.total).then(key_a.cmp(key_b)));
+        for ((target, name), timing) in sorted {
+            let millis = timing.total.as_secs_f64() * 1000.0;
+            writeln!(
+                output,
+                "{millis:>10.3}ms {count:>5}x  {target}::{name}",
+                count = timing.count
+            )?;
+        }
+        Ok(())
+    }
+}
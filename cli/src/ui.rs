@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::RefCell;
+use std::ffi::OsStr;
 use std::io::{IsTerminal as _, Stderr, StderrLock, Stdout, StdoutLock, Write};
 use std::process::{Child, ChildStdin, Stdio};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread::JoinHandle;
 use std::{env, fmt, io, mem};
 
@@ -41,6 +44,9 @@ enum UiOutput {
     BuiltinPaged {
         pager: BuiltinPager,
     },
+    Buffered {
+        output: Box<BufferedOutput>,
+    },
 }
 
 /// A builtin pager
@@ -106,6 +112,24 @@ impl UiOutput {
         }
     }
 
+    fn new_pager(pager_cmd: &CommandNameAndArgs) -> io::Result<UiOutput> {
+        if *pager_cmd == CommandNameAndArgs::String(BUILTIN_PAGER_NAME.into()) {
+            Ok(UiOutput::new_builtin())
+        } else {
+            UiOutput::new_paged(pager_cmd)
+        }
+    }
+
+    fn new_buffered(
+        pager_cmd: &CommandNameAndArgs,
+        max_lines: usize,
+        terminal: UiOutput,
+    ) -> UiOutput {
+        UiOutput::Buffered {
+            output: Box::new(BufferedOutput::new(pager_cmd, max_lines, terminal)),
+        }
+    }
+
     fn new_paged(pager_cmd: &CommandNameAndArgs) -> io::Result<UiOutput> {
         let mut cmd = pager_cmd.to_command();
         tracing::info!(?cmd, "spawning pager");
@@ -115,16 +139,167 @@ impl UiOutput {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Output which is held back until it turns out to be taller than the
+/// terminal, at which point the pager is spawned.
+pub struct BufferedOutput {
+    pager_cmd: CommandNameAndArgs,
+    max_lines: usize,
+    state: RefCell<BufferedState>,
+}
+
+enum BufferedState {
+    Buffering {
+        terminal: UiOutput,
+        chunks: Vec<(OutputStream, Vec<u8>)>,
+        lines: usize,
+    },
+    Spilled(UiOutput),
+}
+
+impl BufferedOutput {
+    fn new(pager_cmd: &CommandNameAndArgs, max_lines: usize, terminal: UiOutput) -> Self {
+        BufferedOutput {
+            pager_cmd: pager_cmd.clone(),
+            max_lines,
+            state: RefCell::new(BufferedState::Buffering {
+                terminal,
+                chunks: vec![],
+                lines: 0,
+            }),
+        }
+    }
+
+    fn write_to(&self, stream: OutputStream, buf: &[u8]) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let (chunks, lines) = match &mut *state {
+            BufferedState::Buffering { chunks, lines, .. } => (chunks, lines),
+            BufferedState::Spilled(output) => return write_unbuffered(output, stream, buf),
+        };
+        *lines += buf.iter().filter(|&&b| b == b'\n').count();
+        match chunks.last_mut() {
+            Some((last_stream, data)) if *last_stream == stream => data.extend_from_slice(buf),
+            _ => chunks.push((stream, buf.to_vec())),
+        }
+        if *lines < self.max_lines {
+            return Ok(());
+        }
+
+        let BufferedState::Buffering {
+            terminal, chunks, ..
+        } = mem::replace(
+            &mut *state,
+            BufferedState::Spilled(UiOutput::new_terminal()),
+        )
+        else {
+            unreachable!();
+        };
+        let output = match UiOutput::new_pager(&self.pager_cmd) {
+            Ok(pager_output) => pager_output,
+            Err(e) => {
+                // The pager executable couldn't be found or couldn't be run
+                let warning = format!(
+                    "Warning: Failed to spawn pager '{name}': {e}. Consider using the \
+                     `:builtin` pager.\n",
+                    name = self.pager_cmd.split_name(),
+                );
+                write_unbuffered(&terminal, OutputStream::Stderr, warning.as_bytes()).ok();
+                terminal
+            }
+        };
+        let result = chunks
+            .iter()
+            .try_for_each(|(stream, data)| write_unbuffered(&output, *stream, data));
+        *state = BufferedState::Spilled(output);
+        result
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match &*self.state.borrow() {
+            BufferedState::Buffering { .. } => Ok(()),
+            BufferedState::Spilled(UiOutput::Terminal { stdout, stderr }) => {
+                stdout.lock().flush()?;
+                stderr.lock().flush()
+            }
+            BufferedState::Spilled(UiOutput::Paged { child_stdin, .. }) => {
+                let mut w = child_stdin;
+                w.flush()
+            }
+            BufferedState::Spilled(UiOutput::BuiltinPaged { .. } | UiOutput::Buffered { .. }) => {
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the buffered output to the terminal if it hasn't been paged.
+    /// Returns the output the content went to, which should be finalized.
+    fn finish(self) -> io::Result<UiOutput> {
+        match self.state.into_inner() {
+            BufferedState::Buffering {
+                terminal, chunks, ..
+            } => {
+                for (stream, data) in &chunks {
+                    write_unbuffered(&terminal, *stream, data)?;
+                }
+                Ok(terminal)
+            }
+            BufferedState::Spilled(output) => Ok(output),
+        }
+    }
+}
+
+fn write_unbuffered(output: &UiOutput, stream: OutputStream, buf: &[u8]) -> io::Result<()> {
+    match output {
+        UiOutput::Terminal { stdout, stderr } => match stream {
+            OutputStream::Stdout => stdout.lock().write_all(buf),
+            OutputStream::Stderr => stderr.lock().write_all(buf),
+        },
+        UiOutput::Paged { child_stdin, .. } => {
+            let mut w = child_stdin;
+            w.write_all(buf)
+        }
+        UiOutput::BuiltinPaged { pager } => {
+            let mut w = pager;
+            w.write_all(buf)
+        }
+        UiOutput::Buffered { .. } => unreachable!("buffered output shouldn't be nested"),
+    }
+}
+
+/// Writer to the stdout or stderr stream of `BufferedOutput`.
+pub struct BufferedWriter<'a> {
+    output: &'a BufferedOutput,
+    stream: OutputStream,
+}
+
+impl Write for BufferedWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write_to(self.stream, buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
 pub enum UiStdout<'a> {
     Terminal(StdoutLock<'static>),
     Paged(&'a ChildStdin),
     Builtin(&'a BuiltinPager),
+    Buffered(BufferedWriter<'a>),
 }
 
 pub enum UiStderr<'a> {
     Terminal(StderrLock<'static>),
     Paged(&'a ChildStdin),
     Builtin(&'a BuiltinPager),
+    Buffered(BufferedWriter<'a>),
 }
 
 macro_rules! for_outputs {
@@ -133,6 +308,7 @@ macro_rules! for_outputs {
             $ty::Terminal($pat) => $expr,
             $ty::Paged($pat) => $expr,
             $ty::Builtin($pat) => $expr,
+            $ty::Buffered($pat) => $expr,
         }
     };
 }
@@ -165,13 +341,40 @@ impl Write for UiStderr<'_> {
     }
 }
 
+/// Destination of status messages, which can be set by embedders to separate
+/// them from errors written to stderr.
+pub type StatusSink = Arc<Mutex<dyn Write + Send>>;
+
+/// Writes to the status sink, locking it per write so that nested status
+/// writers don't deadlock.
+struct StatusSinkWriter<'a>(&'a Mutex<dyn Write + Send>);
+
+impl Write for StatusSinkWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .flush()
+    }
+}
+
 pub struct Ui {
     quiet: bool,
+    assume_yes: bool,
+    wrap_errors: bool,
     pager_cmd: CommandNameAndArgs,
     paginate: PaginationChoice,
     progress_indicator: bool,
     formatter_factory: FormatterFactory,
     output: UiOutput,
+    status_sink: Option<StatusSink>,
 }
 
 fn progress_indicator_setting(config: &config::Config) -> bool {
@@ -221,18 +424,28 @@ fn color_setting(config: &config::Config) -> ColorChoice {
         .unwrap_or_default()
 }
 
+/// Returns whether to color the output, and whether to use debug colors.
+fn resolve_color(choice: ColorChoice, terminal: bool, term: Option<&OsStr>) -> (bool, bool) {
+    match choice {
+        ColorChoice::Always => (true, false),
+        ColorChoice::Never => (false, false),
+        ColorChoice::Debug => (true, true),
+        // Dumb terminals don't understand ANSI escape codes.
+        ColorChoice::Auto => (terminal && term != Some(OsStr::new("dumb")), false),
+    }
+}
+
 fn prepare_formatter_factory(
     config: &config::Config,
     stdout: &Stdout,
 ) -> Result<FormatterFactory, config::ConfigError> {
     let terminal = stdout.is_terminal();
-    let (color, debug) = match color_setting(config) {
-        ColorChoice::Always => (true, false),
-        ColorChoice::Never => (false, false),
-        ColorChoice::Debug => (true, true),
-        ColorChoice::Auto => (terminal, false),
-    };
+    let term = env::var_os("TERM");
+    let (color, debug) = resolve_color(color_setting(config), terminal, term.as_deref());
     if color {
+        // crossterm would otherwise suppress colors if $NO_COLOR is set, but
+        // we've already taken it into account.
+        crossterm::style::force_color_output(true);
         FormatterFactory::color(config, debug)
     } else if terminal {
         // Sanitize ANSI escape codes if we're printing to a terminal. Doesn't
@@ -247,12 +460,23 @@ fn be_quiet(config: &config::Config) -> bool {
     config.get_bool("ui.quiet").unwrap_or_default()
 }
 
+fn assume_yes_setting(config: &config::Config) -> bool {
+    config.get_bool("ui.assume-yes").unwrap_or_default()
+}
+
+fn wrap_errors_setting(config: &config::Config) -> bool {
+    config.get_bool("ui.wrap-errors").unwrap_or_default()
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
 #[serde(rename_all(deserialize = "kebab-case"))]
 pub enum PaginationChoice {
     Never,
     #[default]
     Auto,
+    /// Like `Auto`, but the pager is spawned only if the output doesn't fit
+    /// in the terminal.
+    AutoLong,
 }
 
 fn pagination_setting(config: &config::Config) -> Result<PaginationChoice, CommandError> {
@@ -274,16 +498,26 @@ impl Ui {
         let progress_indicator = progress_indicator_setting(config);
         Ok(Ui {
             quiet,
+            assume_yes: assume_yes_setting(config),
+            wrap_errors: wrap_errors_setting(config),
             formatter_factory,
             pager_cmd: pager_setting(config)?,
             paginate: pagination_setting(config)?,
             progress_indicator,
             output: UiOutput::new_terminal(),
+            status_sink: None,
         })
     }
 
+    /// Routes status messages to the given `sink` instead of stderr.
+    pub fn set_status_sink(&mut self, sink: StatusSink) {
+        self.status_sink = Some(sink);
+    }
+
     pub fn reset(&mut self, config: &config::Config) -> Result<(), CommandError> {
         self.quiet = be_quiet(config);
+        self.assume_yes = assume_yes_setting(config);
+        self.wrap_errors = wrap_errors_setting(config);
         self.paginate = pagination_setting(config)?;
         self.pager_cmd = pager_setting(config)?;
         self.progress_indicator = progress_indicator_setting(config);
@@ -296,17 +530,24 @@ impl Ui {
     pub fn request_pager(&mut self) {
         match self.paginate {
             PaginationChoice::Never => return,
-            PaginationChoice::Auto => {}
+            PaginationChoice::Auto | PaginationChoice::AutoLong => {}
         }
 
         match self.output {
             UiOutput::Terminal { .. } if io::stdout().is_terminal() => {
-                if self.pager_cmd == CommandNameAndArgs::String(BUILTIN_PAGER_NAME.into()) {
-                    self.output = UiOutput::new_builtin();
-                    return;
+                if self.paginate == PaginationChoice::AutoLong {
+                    // If the terminal height is unknown, page immediately.
+                    if let Some(height) = term_height() {
+                        self.output = UiOutput::new_buffered(
+                            &self.pager_cmd,
+                            height.into(),
+                            UiOutput::new_terminal(),
+                        );
+                        return;
+                    }
                 }
 
-                match UiOutput::new_paged(&self.pager_cmd) {
+                match UiOutput::new_pager(&self.pager_cmd) {
                     Ok(pager_output) => {
                         self.output = pager_output;
                     }
@@ -322,7 +563,10 @@ impl Ui {
                     }
                 }
             }
-            UiOutput::Terminal { .. } | UiOutput::BuiltinPaged { .. } | UiOutput::Paged { .. } => {}
+            UiOutput::Terminal { .. }
+            | UiOutput::BuiltinPaged { .. }
+            | UiOutput::Paged { .. }
+            | UiOutput::Buffered { .. } => {}
         }
     }
 
@@ -343,6 +587,10 @@ impl Ui {
             UiOutput::Terminal { stdout, .. } => UiStdout::Terminal(stdout.lock()),
             UiOutput::Paged { child_stdin, .. } => UiStdout::Paged(child_stdin),
             UiOutput::BuiltinPaged { pager } => UiStdout::Builtin(pager),
+            UiOutput::Buffered { output } => UiStdout::Buffered(BufferedWriter {
+                output,
+                stream: OutputStream::Stdout,
+            }),
         }
     }
 
@@ -360,6 +608,10 @@ impl Ui {
             UiOutput::Terminal { stderr, .. } => UiStderr::Terminal(stderr.lock()),
             UiOutput::Paged { child_stdin, .. } => UiStderr::Paged(child_stdin),
             UiOutput::BuiltinPaged { pager } => UiStderr::Builtin(pager),
+            UiOutput::Buffered { output } => UiStderr::Buffered(BufferedWriter {
+                output,
+                stream: OutputStream::Stderr,
+            }),
         }
     }
 
@@ -375,16 +627,36 @@ impl Ui {
             UiOutput::Paged { child_stdin, .. } => Ok(duplicate_child_stdin(child_stdin)?.into()),
             // Stderr does not get redirected through the built-in pager.
             UiOutput::BuiltinPaged { .. } => Ok(Stdio::inherit()),
+            // Output written by a child process can't be held back.
+            UiOutput::Buffered { .. } => Ok(Stdio::inherit()),
+        }
+    }
+
+    /// Whether the command output is displayed in a terminal, either directly
+    /// or through a pager.
+    pub fn is_stdout_terminal(&self) -> bool {
+        match &self.output {
+            UiOutput::Terminal { stdout, .. } => stdout.is_terminal(),
+            // The output is only paged or buffered if stdout is a terminal.
+            UiOutput::Paged { .. } | UiOutput::BuiltinPaged { .. } | UiOutput::Buffered { .. } => {
+                true
+            }
         }
     }
 
+    /// Whether stderr is connected to a terminal.
+    pub fn is_stderr_terminal(&self) -> bool {
+        io::stderr().is_terminal()
+    }
+
     /// Whether continuous feedback should be displayed for long-running
     /// operations
     pub fn use_progress_indicator(&self) -> bool {
         match &self.output {
-            UiOutput::Terminal { stderr, .. } => self.progress_indicator && stderr.is_terminal(),
+            UiOutput::Terminal { .. } => self.progress_indicator && self.is_stderr_terminal(),
             UiOutput::Paged { .. } => false,
             UiOutput::BuiltinPaged { .. } => false,
+            UiOutput::Buffered { .. } => false,
         }
     }
 
@@ -395,9 +667,13 @@ impl Ui {
     }
 
     /// Writer to print an update that's not part of the command's main output.
+    ///
+    /// The update is written to the status sink if set, or to stderr.
     pub fn status(&self) -> Box<dyn Write + '_> {
         if self.quiet {
             Box::new(io::sink())
+        } else if let Some(sink) = &self.status_sink {
+            Box::new(StatusSinkWriter(sink))
         } else {
             Box::new(self.stderr())
         }
@@ -406,7 +682,13 @@ impl Ui {
     /// A formatter to print an update that's not part of the command's main
     /// output. Returns `None` if `--quiet` was requested.
     pub fn status_formatter(&self) -> Option<Box<dyn Formatter + '_>> {
-        (!self.quiet).then(|| self.stderr_formatter())
+        if self.quiet {
+            None
+        } else if let Some(sink) = &self.status_sink {
+            Some(self.new_formatter(StatusSinkWriter(sink)))
+        } else {
+            Some(self.stderr_formatter())
+        }
     }
 
     /// Writer to print hint with the default "Hint: " heading.
@@ -417,10 +699,15 @@ impl Ui {
     }
 
     /// Writer to print hint without the "Hint: " heading.
+    ///
+    /// Hints are written to stderr even if the status sink is set, since they
+    /// usually accompany errors or warnings.
     pub fn hint_no_heading(&self) -> LabeledWriter<Box<dyn Formatter + '_>, &'static str> {
-        let formatter = self
-            .status_formatter()
-            .unwrap_or_else(|| Box::new(PlainTextFormatter::new(io::sink())));
+        let formatter = if self.quiet {
+            Box::new(PlainTextFormatter::new(io::sink()))
+        } else {
+            self.stderr_formatter()
+        };
         LabeledWriter::new(formatter, "hint")
     }
 
@@ -484,6 +771,15 @@ impl Ui {
             UiOutput::BuiltinPaged { pager } => {
                 pager.finalize();
             }
+            UiOutput::Buffered { output } => match output.finish() {
+                Ok(output) => {
+                    self.output = output;
+                    self.finalize_pager();
+                }
+                Err(e) => {
+                    writeln!(self.warning_default(), "Failed to write output: {e}").ok();
+                }
+            },
             _ => { /* no-op */ }
         }
     }
@@ -551,13 +847,18 @@ impl Ui {
         }
     }
 
-    /// Prompts for a yes-or-no response, with yes = true and no = false.
+    /// Prompts for a yes-or-no response, with yes = true and no = false. If
+    /// `--yes` was specified, the answer is yes without prompting.
     pub fn prompt_yes_no(&self, prompt: &str, default: Option<bool>) -> io::Result<bool> {
         let default_str = match &default {
             Some(true) => "(Yn)",
             Some(false) => "(yN)",
             None => "(yn)",
         };
+        if self.assume_yes {
+            writeln!(self.stdout(), "{prompt} {default_str}: y")?;
+            return Ok(true);
+        }
         let default_choice = default.map(|c| if c { "Y" } else { "N" });
 
         let choice = self.prompt_choice(
@@ -569,7 +870,7 @@ impl Ui {
     }
 
     pub fn prompt_password(&self, prompt: &str) -> io::Result<String> {
-        if !io::stdout().is_terminal() {
+        if !self.is_stdout_terminal() {
             return Err(io::Error::new(
                 io::ErrorKind::Unsupported,
                 "Cannot prompt for input since the output is not connected to a terminal",
@@ -581,6 +882,13 @@ impl Ui {
     pub fn term_width(&self) -> Option<u16> {
         term_width()
     }
+
+    /// Width at which error and hint messages should be wrapped, or `None` if
+    /// `ui.wrap-errors` is disabled.
+    pub fn error_wrap_width(&self) -> Option<usize> {
+        self.wrap_errors
+            .then(|| self.term_width().map_or(80, usize::from))
+    }
 }
 
 #[derive(Debug)]
@@ -637,6 +945,14 @@ fn duplicate_child_stdin(stdin: &ChildStdin) -> io::Result<std::os::windows::io:
     stdin.as_handle().try_clone_to_owned()
 }
 
+fn term_height() -> Option<u16> {
+    if let Some(lines) = env::var("LINES").ok().and_then(|s| s.parse().ok()) {
+        Some(lines)
+    } else {
+        crossterm::terminal::size().ok().map(|(_, rows)| rows)
+    }
+}
+
 fn term_width() -> Option<u16> {
     if let Some(cols) = env::var("COLUMNS").ok().and_then(|s| s.parse().ok()) {
         Some(cols)
@@ -644,3 +960,144 @@ fn term_width() -> Option<u16> {
         crossterm::terminal::size().ok().map(|(cols, _)| cols)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_color() {
+        let dumb = Some(OsStr::new("dumb"));
+        let xterm = Some(OsStr::new("xterm-256color"));
+
+        assert_eq!(resolve_color(ColorChoice::Auto, true, xterm), (true, false));
+        assert_eq!(resolve_color(ColorChoice::Auto, true, None), (true, false));
+        assert_eq!(
+            resolve_color(ColorChoice::Auto, false, xterm),
+            (false, false)
+        );
+        // Dumb terminals don't get colors unless forced
+        assert_eq!(resolve_color(ColorChoice::Auto, true, dumb), (false, false));
+        assert_eq!(
+            resolve_color(ColorChoice::Always, true, dumb),
+            (true, false)
+        );
+        assert_eq!(resolve_color(ColorChoice::Debug, true, dumb), (true, true));
+        assert_eq!(
+            resolve_color(ColorChoice::Never, true, xterm),
+            (false, false)
+        );
+    }
+
+    #[test]
+    fn test_prompt_yes_no_assume_yes() {
+        let config = config::Config::builder()
+            .set_override("ui.assume-yes", true)
+            .unwrap()
+            .set_override("ui.pager", "less")
+            .unwrap()
+            .set_override("ui.paginate", "never")
+            .unwrap()
+            .build()
+            .unwrap();
+        let ui = Ui::with_config(&config).unwrap();
+        assert!(ui.prompt_yes_no("Continue?", Some(false)).unwrap());
+    }
+
+    /// Returns a command which saves its stdin to the given file.
+    #[cfg(unix)]
+    fn capture_cmd(path: &std::path::Path) -> CommandNameAndArgs {
+        let args = vec![
+            "sh".to_owned(),
+            "-c".to_owned(),
+            r#"cat > "$0""#.to_owned(),
+            path.to_str().unwrap().to_owned(),
+        ];
+        CommandNameAndArgs::Vec(args.try_into().unwrap())
+    }
+
+    #[cfg(unix)]
+    fn wait_output(output: UiOutput) {
+        match output {
+            UiOutput::Paged {
+                mut child,
+                child_stdin,
+            } => {
+                drop(child_stdin);
+                assert!(child.wait().unwrap().success());
+            }
+            _ => panic!("unexpected output type"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_buffered_output_short() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let terminal_path = temp_dir.path().join("terminal");
+        let pager_path = temp_dir.path().join("pager");
+        let terminal = UiOutput::new_paged(&capture_cmd(&terminal_path)).unwrap();
+        let output = BufferedOutput::new(&capture_cmd(&pager_path), 3, terminal);
+
+        output.write_to(OutputStream::Stdout, b"out 1\n").unwrap();
+        output.write_to(OutputStream::Stderr, b"err 1\n").unwrap();
+        output.write_to(OutputStream::Stdout, b"out 2").unwrap();
+        output.flush().unwrap();
+        wait_output(output.finish().unwrap());
+
+        assert_eq!(
+            std::fs::read_to_string(&terminal_path).unwrap(),
+            "out 1\nerr 1\nout 2"
+        );
+        // The pager shouldn't be spawned
+        assert!(!pager_path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_buffered_output_spilled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let terminal_path = temp_dir.path().join("terminal");
+        let pager_path = temp_dir.path().join("pager");
+        let terminal = UiOutput::new_paged(&capture_cmd(&terminal_path)).unwrap();
+        let output = BufferedOutput::new(&capture_cmd(&pager_path), 3, terminal);
+
+        output.write_to(OutputStream::Stdout, b"out 1\n").unwrap();
+        output.write_to(OutputStream::Stderr, b"err 1\n").unwrap();
+        output.write_to(OutputStream::Stdout, b"out 2\n").unwrap();
+        assert!(matches!(
+            *output.state.borrow(),
+            BufferedState::Spilled(UiOutput::Paged { .. })
+        ));
+        output.write_to(OutputStream::Stderr, b"err 2\n").unwrap();
+        wait_output(output.finish().unwrap());
+
+        assert_eq!(
+            std::fs::read_to_string(&pager_path).unwrap(),
+            "out 1\nerr 1\nout 2\nerr 2\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_buffered_output_pager_spawn_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let terminal_path = temp_dir.path().join("terminal");
+        let terminal = UiOutput::new_paged(&capture_cmd(&terminal_path)).unwrap();
+        let pager_cmd = CommandNameAndArgs::String("/nonexistent/pager".to_owned());
+        let output = BufferedOutput::new(&pager_cmd, 2, terminal);
+
+        output.write_to(OutputStream::Stdout, b"out 1\n").unwrap();
+        output.write_to(OutputStream::Stderr, b"err 1\n").unwrap();
+        output.write_to(OutputStream::Stdout, b"out 2\n").unwrap();
+        wait_output(output.finish().unwrap());
+
+        let content = std::fs::read_to_string(&terminal_path).unwrap();
+        let (warning, rest) = content.split_once('\n').unwrap();
+        assert!(
+            warning.starts_with("Warning: Failed to spawn pager '/nonexistent/pager': "),
+            "{warning}"
+        );
+        assert_eq!(rest, "out 1\nerr 1\nout 2\n");
+    }
+}
@@ -14,7 +14,8 @@
 
 use std::path::PathBuf;
 use std::process::exit;
-use std::{env, fs};
+use std::time::Duration;
+use std::{env, fs, thread};
 
 use clap::Parser;
 use itertools::Itertools;
@@ -46,10 +47,23 @@ fn main() {
         match parts.as_slice() {
             [""] => {}
             ["fail"] => exit(1),
+            ["sleep", secs] => {
+                thread::sleep(Duration::from_secs(secs.parse().unwrap()));
+            }
             ["dump", dest] => {
                 let dest_path = edit_script_path.parent().unwrap().join(dest);
                 fs::copy(&args.file, dest_path).unwrap();
             }
+            ["dumpcwd", dest] => {
+                let dest_path = edit_script_path.parent().unwrap().join(dest);
+                let cwd = env::current_dir().unwrap();
+                fs::write(dest_path, cwd.to_str().unwrap()).unwrap();
+            }
+            ["dumpenv", name, dest] => {
+                let dest_path = edit_script_path.parent().unwrap().join(dest);
+                let value = env::var(name).unwrap_or_else(|_| "<unset>".to_owned());
+                fs::write(dest_path, value).unwrap();
+            }
             ["expect"] => {
                 let actual = String::from_utf8(fs::read(&args.file).unwrap()).unwrap();
                 if actual != payload {
@@ -0,0 +1,127 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::fs::File;
+use std::io::Write as _;
+use std::process::{Command, ExitCode};
+use std::sync::{Arc, Mutex};
+
+use jj_cli::cli_util::{CliRunner, CommandHelper, RevisionArg};
+use jj_cli::command_error::CommandError;
+use jj_cli::ui::Ui;
+use jj_lib::object_id::ObjectId as _;
+
+#[derive(clap::Parser, Clone, Debug)]
+enum CustomCommand {
+    /// Fails if the working copy isn't clean
+    RequireClean,
+    /// Starts a working-copy mutation after running the given command
+    ///
+    /// The command runs after the repo is loaded, so it can update the
+    /// working copy concurrently with this command.
+    MutateWorkingCopy {
+        #[arg(last = true, required = true)]
+        concurrent_command: Vec<String>,
+    },
+    /// Edits the given revision without checking whether it's immutable
+    EditUnchecked { revision: RevisionArg },
+    /// Lists the tracked and untracked remote branches
+    RemoteBranches,
+    /// Prints the commit id of the given revision
+    ResolveSingleCommitId { revision: RevisionArg },
+    /// Prints the descriptions of the working-copy parents
+    WcParents,
+}
+
+fn run_custom_command(
+    ui: &mut Ui,
+    command_helper: &CommandHelper,
+    command: CustomCommand,
+) -> Result<(), CommandError> {
+    match command {
+        CustomCommand::RequireClean => {
+            let mut workspace_command = command_helper.workspace_helper(ui)?;
+            workspace_command.require_clean_working_copy(ui)?;
+            writeln!(ui.status(), "The working copy is clean")?;
+            Ok(())
+        }
+        CustomCommand::MutateWorkingCopy { concurrent_command } => {
+            let mut workspace_command = command_helper.workspace_helper(ui)?;
+            let (program, args) = concurrent_command.split_first().unwrap();
+            let status = Command::new(program).args(args).status()?;
+            assert!(status.success(), "concurrent command failed: {status}");
+            let (locked_ws, wc_commit) = workspace_command.start_working_copy_mutation()?;
+            drop(locked_ws);
+            writeln!(ui.status(), "Working copy commit: {}", wc_commit.id().hex())?;
+            Ok(())
+        }
+        CustomCommand::EditUnchecked { revision } => {
+            let mut workspace_command = command_helper.workspace_helper(ui)?;
+            let commit = workspace_command.resolve_single_rev(&revision)?;
+            let mut tx = workspace_command.start_transaction();
+            tx.edit(&commit)?;
+            tx.finish(ui, "edit commit without checking immutability")
+        }
+        CustomCommand::RemoteBranches => {
+            let workspace_command = command_helper.workspace_helper(ui)?;
+            for name in workspace_command.tracked_remote_branches() {
+                writeln!(ui.stdout(), "tracked: {name}")?;
+            }
+            for name in workspace_command.untracked_remote_branches() {
+                writeln!(ui.stdout(), "untracked: {name}")?;
+            }
+            Ok(())
+        }
+        CustomCommand::ResolveSingleCommitId { revision } => {
+            let workspace_command = command_helper.workspace_helper(ui)?;
+            let commit_id = workspace_command.resolve_single_commit_id(&revision)?;
+            writeln!(ui.stdout(), "{}", commit_id.hex())?;
+            Ok(())
+        }
+        CustomCommand::WcParents => {
+            let workspace_command = command_helper.workspace_helper(ui)?;
+            let parents = workspace_command.wc_parent_commits()?;
+            writeln!(ui.stdout(), "{} parents", parents.len())?;
+            for commit in parents {
+                writeln!(ui.stdout(), "{}", commit.description().trim_end())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// A fake application embedding `jj`, useful for testing
+///
+/// `fake-embedder` runs the `jj` command given as arguments, and then prints
+/// the operation id reported by `CliRunner::run_and_report()` to stderr. It
+/// also provides custom commands calling `jj-cli` APIs that no built-in
+/// command uses.
+///
+/// If `FAKE_EMBEDDER_STATUS_FILE` is set, status messages are written to that
+/// file instead of stderr.
+fn main() -> ExitCode {
+    let mut runner = CliRunner::init().add_subcommand(run_custom_command);
+    if let Some(path) = env::var_os("FAKE_EMBEDDER_STATUS_FILE") {
+        let file = File::create(path).unwrap();
+        runner = runner.set_status_sink(Arc::new(Mutex::new(file)));
+    }
+    let outcome = runner.run_and_report();
+    let operation_id = outcome
+        .operation_id
+        .as_ref()
+        .map_or_else(|| "none".to_owned(), |id| id.hex());
+    eprintln!("operation_id: {operation_id}");
+    outcome.exit_code
+}
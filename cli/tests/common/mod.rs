@@ -83,7 +83,18 @@ impl Default for TestEnvironment {
 
 impl TestEnvironment {
     pub fn jj_cmd(&self, current_dir: &Path, args: &[&str]) -> assert_cmd::Command {
-        let mut cmd = assert_cmd::Command::cargo_bin("jj").unwrap();
+        self.cargo_bin_cmd("jj", current_dir, args)
+    }
+
+    /// Sets up a command running the binary `name` of this package, with the
+    /// same environment as `jj_cmd()`.
+    pub fn cargo_bin_cmd(
+        &self,
+        name: &str,
+        current_dir: &Path,
+        args: &[&str],
+    ) -> assert_cmd::Command {
+        let mut cmd = assert_cmd::Command::cargo_bin(name).unwrap();
         cmd.current_dir(current_dir);
         cmd.args(args);
         cmd.env_clear();
@@ -208,6 +219,14 @@ impl TestEnvironment {
         self.normalize_output(&get_stderr_string(&assert))
     }
 
+    /// Run a `jj` command, check that it failed with code 5 (for no workspace
+    /// found), and return its stderr
+    #[must_use]
+    pub fn jj_cmd_no_workspace(&self, current_dir: &Path, args: &[&str]) -> String {
+        let assert = self.jj_cmd(current_dir, args).assert().code(5).stdout("");
+        self.normalize_output(&get_stderr_string(&assert))
+    }
+
     /// Run a `jj` command and check that it failed with code 2 (for invalid
     /// usage)
     #[must_use]
@@ -55,6 +55,7 @@ mod test_resolve_command;
 mod test_restore_command;
 mod test_revset_output;
 mod test_root;
+mod test_run_and_report;
 mod test_shell_completion;
 mod test_show_command;
 mod test_sparse_command;
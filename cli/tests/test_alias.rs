@@ -72,6 +72,7 @@ fn test_alias_bad_name() {
     Usage: jj [OPTIONS] <COMMAND>
 
     For more information, try '--help'.
+    Hint: Use `jj -h` for a list of available commands.
     "###);
 }
 
@@ -97,9 +98,16 @@ fn test_alias_calls_empty_command() {
     insta::assert_snapshot!(stderr.lines().next().unwrap_or_default(), @r###"
     error: 'jj' requires a subcommand but one was not provided
     "###);
-    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["empty_command_with_opts"]);
-    insta::assert_snapshot!(stderr.lines().next().unwrap_or_default(), @r###"
-    error: 'jj' requires a subcommand but one was not provided
+    // An alias consisting of global flags only runs the default command
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["empty_command_with_opts"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 230dd059
+    │  (empty) (no description set)
+    ◉  zzzzzzzz root() 00000000
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Hint: Use `jj -h` for a list of available commands.
+    Run `jj config set --user ui.default-command log` to disable this message.
     "###);
 }
 
@@ -119,6 +127,7 @@ fn test_alias_calls_unknown_command() {
     Usage: jj [OPTIONS] <COMMAND>
 
     For more information, try '--help'.
+    Hint: Use `jj -h` for a list of available commands.
     "###);
 }
 
@@ -195,6 +204,32 @@ fn test_alias_recursive() {
     "###);
 }
 
+#[test]
+fn test_alias_max_expansion_depth() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // A chain of 101 distinct aliases: a0 -> a1 -> ... -> a100 -> log
+    let mut config = "[aliases]\n".to_owned();
+    for i in 0..100 {
+        config.push_str(&format!("a{i} = [\"a{}\"]\n", i + 1));
+    }
+    config.push_str("a100 = [\"log\"]\n");
+    test_env.add_config(&config);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["a0"]);
+    let (message, hint) = stderr.split_once('\n').unwrap();
+    insta::assert_snapshot!(message, @"Error: Alias expansion exceeded the maximum depth of 100");
+    assert!(hint.starts_with("Hint: Expanded aliases: a0 -> a1 -> a2 -> "));
+    assert!(hint.ends_with(" -> a99 -> a100\n"));
+
+    // Shorter chains are fine
+    let stdout = test_env.jj_cmd_success(&repo_path, &["a1", "-r", "root()"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  zzzzzzzz root() 00000000
+    "###);
+}
+
 #[test]
 fn test_alias_global_args_before_and_after() {
     let test_env = TestEnvironment::default();
@@ -246,6 +281,37 @@ fn test_alias_global_args_in_definition() {
     "###);
 }
 
+#[test]
+fn test_alias_starting_with_global_args() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+    ui.default-command = ["log", "-T", "commit_id", "-r", "all()"]
+    aliases.l = ["log", "-T", "commit_id", "-r", "all()"]
+    aliases.cl = ["--color=always", "log", "-T", "commit_id", "-r", "all()"]
+    aliases.old-l = ["--at-op", "@-", "l"]
+    aliases.old = ["--at-op", "@-"]
+    "#,
+    );
+
+    // Global flags followed by a command
+    let stdout = test_env.jj_cmd_success(&repo_path, &["cl"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  [38;5;4m230dd059e1b059aefc0da06a2e5a7dbf22362f22[39m
+    ◉  [38;5;4m0000000000000000000000000000000000000000[39m
+    "###);
+    // Global flags followed by another alias
+    let stdout = test_env.jj_cmd_success(&repo_path, &["old-l"]);
+    insta::assert_snapshot!(stdout, @"◉  0000000000000000000000000000000000000000");
+    // Global flags only, which runs the default command
+    let stdout = test_env.jj_cmd_success(&repo_path, &["old"]);
+    insta::assert_snapshot!(stdout, @"◉  0000000000000000000000000000000000000000");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["old", "--color=always"]);
+    insta::assert_snapshot!(stdout, @"◉  [38;5;4m0000000000000000000000000000000000000000[39m");
+}
+
 #[test]
 fn test_alias_invalid_definition() {
     let test_env = TestEnvironment::default();
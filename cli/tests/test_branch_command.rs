@@ -14,7 +14,7 @@
 
 use std::path::Path;
 
-use crate::common::TestEnvironment;
+use crate::common::{get_stdout_string, TestEnvironment};
 
 #[test]
 fn test_branch_multiple_names() {
@@ -1065,10 +1065,14 @@ fn test_branch_track_untrack_patterns() {
     // Track local branch
     test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
     insta::assert_snapshot!(
-        test_env.jj_cmd_cli_error(&repo_path, &["branch", "track", "main"]), @r###"
-    error: invalid value 'main' for '<BRANCH@REMOTE>...': remote branch must be specified in branch@remote form
-
-    For more information, try '--help'.
+        test_env.jj_cmd_failure(&repo_path, &["branch", "track", "main"]), @r###"
+    Error: Invalid remote branch pattern "main": remote branch must be specified in branch@remote form
+    Hint: Remote branches are specified in branch@remote form, e.g. main@origin
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["branch", "untrack", "main@origin", "foo:main@origin"]), @r###"
+    Error: Invalid remote branch pattern "foo:main@origin": Invalid string pattern kind "foo:"
+    Hint: Remote branches are specified in branch@remote form, e.g. main@origin
     "###);
 
     // Track/untrack unknown branch
@@ -1750,3 +1754,38 @@ fn get_branch_output(test_env: &TestEnvironment, repo_path: &Path) -> String {
     // --quiet to suppress deleted branches hint
     test_env.jj_cmd_success(repo_path, &["branch", "list", "--all-remotes", "--quiet"])
 }
+
+#[test]
+fn test_branch_tracked_untracked_remote_branches() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    for remote in ["upstream", "origin"] {
+        let git_repo_path = test_env.env_root().join(remote);
+        git2::Repository::init_bare(git_repo_path).unwrap();
+        test_env.jj_cmd_ok(
+            &repo_path,
+            &["git", "remote", "add", remote, &format!("../{remote}")],
+        );
+    }
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "commit"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main", "feature"]);
+    for remote in ["upstream", "origin"] {
+        test_env.jj_cmd_ok(&repo_path, &["git", "push", "--remote", remote, "--all"]);
+    }
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["branch", "untrack", "main@upstream", "feature@origin"],
+    );
+
+    let assert = test_env
+        .cargo_bin_cmd("fake-embedder", &repo_path, &["remote-branches"])
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    tracked: feature@upstream
+    tracked: main@origin
+    untracked: feature@origin
+    untracked: main@upstream
+    "###);
+}
@@ -718,14 +718,14 @@ fn test_log_immutable() {
     test_env.add_config("revset-aliases.'immutable_heads()' = 'unknown_fn()'");
     let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r::", "-T", template]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: Failed to parse template: Failed to parse revset
+    Error: Failed to parse template: Invalid `revset-aliases.immutable_heads()`
     Caused by:
     1:  --> 5:10
       |
     5 |       if(immutable, "[immutable]"),
       |          ^-------^
       |
-      = Failed to parse revset
+      = Invalid `revset-aliases.immutable_heads()`
     2:  --> 1:1
       |
     1 | unknown_fn()
@@ -737,14 +737,14 @@ fn test_log_immutable() {
     test_env.add_config("revset-aliases.'immutable_heads()' = 'unknown_symbol'");
     let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r::", "-T", template]);
     insta::assert_snapshot!(stderr, @r###"
-    Error: Failed to parse template: Failed to evaluate revset
+    Error: Failed to parse template: Invalid `revset-aliases.immutable_heads()`
     Caused by:
     1:  --> 5:10
       |
     5 |       if(immutable, "[immutable]"),
       |          ^-------^
       |
-      = Failed to evaluate revset
+      = Invalid `revset-aliases.immutable_heads()`
     2: Revision "unknown_symbol" doesn't exist
     "###);
 }
@@ -870,3 +870,37 @@ fn test_log_contained_in() {
     Hint: Did you mean "main"?
     "###);
 }
+
+#[test]
+fn test_working_copy_updated_template() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+    test_env.add_config(
+        r#"templates.working_copy_updated = '''
+        "Now editing " ++ change_id.short(8) ++ "\n" ++
+        parents.map(|c| "  on top of " ++ c.description().first_line()).join("\n")
+        '''"#,
+    );
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "-m", "second"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Now editing kkmpptxz
+      on top of first
+    "###);
+
+    // Invalid template is reported early
+    test_env.add_config(r#"templates.working_copy_updated = 'bad_keyword'"#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["new"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse template: Keyword "bad_keyword" doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | bad_keyword
+      | ^---------^
+      |
+      = Keyword "bad_keyword" doesn't exist
+    "###);
+}
@@ -59,6 +59,51 @@ fn test_concurrent_operation_divergence() {
     "###);
 }
 
+#[test]
+fn test_concurrent_operation_divergence_no_auto_resolve() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("operation.auto-resolve-concurrent = false");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "message 1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "message 2", "--at-op", "@-"],
+    );
+
+    // The concurrent operations aren't merged
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Concurrent modification detected, and `operation.auto-resolve-concurrent` is disabled
+    Hint: The divergent operations are:
+      e31015019d90 describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+      48f4a48f3f70 describe commit 230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    Run `jj op log` to inspect them, and merge them by running a command with `--config-toml operation.auto-resolve-concurrent=true`.
+    "###);
+
+    // They can be merged explicitly
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "log",
+            "-T",
+            "description",
+            "--config-toml",
+            "operation.auto-resolve-concurrent=true",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  message 2
+    │ @  message 1
+    ├─╯
+    ◉
+    "###);
+    insta::assert_snapshot!(stderr, @"Concurrent modification detected, resolving automatically.");
+    // Once merged, commands work again without the override
+    test_env.jj_cmd_ok(&repo_path, &["log"]);
+}
+
 #[test]
 fn test_concurrent_operations_auto_rebase() {
     let test_env = TestEnvironment::default();
@@ -102,6 +147,86 @@ fn test_concurrent_operations_auto_rebase() {
     "###);
 }
 
+#[test]
+fn test_concurrent_operations_no_report() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.report-concurrent-resolution = false");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "message 1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "message 2", "--at-op", "@-"],
+    );
+
+    // The concurrent operations are merged silently
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  message 2
+    │ @  message 1
+    ├─╯
+    ◉
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+}
+
+#[test]
+fn test_concurrent_operations_report_args() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.report-concurrent-args = true");
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "message 1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "message 2", "--at-op", "@-"],
+    );
+
+    // The command of each merged operation is printed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Concurrent modification detected, resolving automatically.
+      e31015019d90 jj describe -m 'message 1'
+      48f4a48f3f70 jj describe -m 'message 2' --at-op @-
+    "###);
+}
+
+#[test]
+fn test_concurrent_operations_merge_order() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.report-concurrent-args = true");
+    test_env.add_config(r#"operation.merge-order = "id""#);
+
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "message 1"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "message 2", "--at-op", "@-"],
+    );
+
+    // The operations are merged in the order of their ids
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "-T", "description"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Concurrent modification detected, resolving automatically.
+      48f4a48f3f70 jj describe -m 'message 2' --at-op @-
+      e31015019d90 jj describe -m 'message 1'
+    "###);
+
+    // Invalid value
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "--config-toml=operation.merge-order='random'"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `operation.merge-order`
+    Caused by: enum OperationMergeOrder does not have variant constructor random
+    Hint: Check the `operation.merge-order` value; expected one of `timestamp` or `id`.
+    "###);
+}
+
 #[test]
 fn test_concurrent_operations_wc_modified() {
     let test_env = TestEnvironment::default();
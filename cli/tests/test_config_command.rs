@@ -157,7 +157,7 @@ bar
     """
     "###);
 
-    let stdout = test_env.jj_cmd_success(
+    let (stdout, stderr) = test_env.jj_cmd_ok(
         test_env.env_root(),
         &[
             "config",
@@ -174,6 +174,9 @@ bar
     # """
     multiline = "single"
     "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Unknown top-level config key `multiline` in --config-toml argument
+    "###);
 }
 
 #[test]
@@ -388,6 +391,90 @@ fn test_config_layer_override_env() {
     "###);
 }
 
+#[test]
+fn test_config_file_arg() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(
+        repo_path.join(".jj/repo/config.toml"),
+        "ui.editor = \"repo\"\n",
+    )
+    .unwrap();
+    let file1 = test_env.env_root().join("file1.toml");
+    std::fs::write(&file1, "ui.editor = \"file1\"\nui.pager = \"file1\"\n").unwrap();
+    let file2 = test_env.env_root().join("file2.toml");
+    std::fs::write(&file2, "ui.editor = \"file2\"\n").unwrap();
+
+    // Files override the repo config, and later files take precedence
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "list",
+            "--include-overridden",
+            "ui.editor",
+            "--config-file",
+            file1.to_str().unwrap(),
+            "--config-file",
+            file2.to_str().unwrap(),
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    # ui.editor = "repo"
+    ui.editor = "file2"
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "get",
+            "ui.pager",
+            "--config-file",
+            file1.to_str().unwrap(),
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"file1");
+
+    // --config-toml takes precedence over files
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "config",
+            "get",
+            "ui.editor",
+            "--config-file",
+            file1.to_str().unwrap(),
+            "--config-toml=ui.editor='arg'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"arg");
+
+    // Missing file
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["config", "get", "ui.editor", "--config-file=missing.toml"],
+    );
+    insta::assert_snapshot!(stderr, @r###"Error: Config file "missing.toml" doesn't exist"###);
+
+    // Invalid TOML
+    std::fs::write(&file2, "ui.editor =\n").unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &[
+            "config",
+            "get",
+            "ui.editor",
+            "--config-file",
+            file2.to_str().unwrap(),
+        ],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: expected a value, found a newline at line 1 column 12 in ../file2.toml
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+}
+
 #[test]
 fn test_config_layer_workspace() {
     let test_env = TestEnvironment::default();
@@ -670,7 +757,7 @@ fn test_config_path() {
 #[test]
 fn test_config_edit_repo_outside_repo() {
     let test_env = TestEnvironment::default();
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["config", "edit", "--repo"]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &["config", "edit", "--repo"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -851,7 +938,7 @@ fn test_config_show_paths() {
     Caused by: enum PaginationChoice does not have variant constructor :builtin
     Hint: Check the following config files:
     - $TEST_ENV/config/config.toml
-    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    Hint: Check the `ui.paginate` value; expected one of `never`, `auto`, or `auto-long`.
     "###);
 }
 
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use insta::assert_snapshot;
+use itertools::Itertools as _;
 use regex::Regex;
 
 use crate::common::TestEnvironment;
@@ -241,6 +242,45 @@ fn test_debug_operation_id() {
     );
 }
 
+#[test]
+fn test_debug_commands() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"aliases.l = ["log"]"#);
+
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["debug", "commands"]);
+    let lines = stdout
+        .lines()
+        .filter(|line| line.contains("\tbranch") || line.starts_with("alias"))
+        .join("\n");
+    assert_snapshot!(lines, @r###"
+    command	branch
+    command	branch create	c
+    command	branch delete	d
+    command	branch forget	f
+    command	branch list	l
+    command	branch move	m
+    command	branch rename	r
+    command	branch set	s
+    command	branch track	t
+    command	branch untrack
+    alias	amend
+    alias	co
+    alias	l
+    alias	unamend
+    "###);
+
+    let stdout = test_env.jj_cmd_success(
+        test_env.env_root(),
+        &["debug", "commands", "--format", "json"],
+    );
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    let entry = entries
+        .iter()
+        .find(|entry| entry["name"] == "branch list")
+        .unwrap();
+    assert_snapshot!(serde_json::to_string(entry).unwrap(), @r###"{"aliases":["l"],"hidden":false,"kind":"command","name":"branch list"}"###);
+}
+
 fn filter_index_stats(text: &str) -> String {
     let regex = Regex::new(r"    Name: [0-9a-z]+").unwrap();
     regex.replace_all(text, "    Name: [hash]").to_string()
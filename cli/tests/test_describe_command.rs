@@ -278,6 +278,96 @@ JJ: Lines starting with "JJ: " (like this one) will be removed.
     );
 }
 
+#[test]
+fn test_describe_editor_comment_prefix_and_template() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    test_env.add_config(
+        r##"
+    ui.editor-comment-prefix = "#"
+    templates.draft_commit_description = '"# Editing " ++ change_id.short() ++ "\n"'
+    "##,
+    );
+    let workspace_path = test_env.env_root().join("repo");
+
+    std::fs::write(workspace_path.join("file1"), "foo\n").unwrap();
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        edit_script,
+        [
+            "dump editor",
+            "write\ndescription\n# comment\n#\n#not a comment\nJJ: not a comment",
+        ]
+        .join("\0"),
+    )
+    .unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["describe"]);
+    insta::assert_snapshot!(
+        std::fs::read_to_string(test_env.env_root().join("editor")).unwrap(), @r###"
+
+    # This commit contains the following changes:
+    #     A file1
+
+    # Editing qpvuntsmwlqt
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["log", "--no-graph", "-r@", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    description
+    #
+    #not a comment
+    JJ: not a comment
+    "###);
+}
+
+#[test]
+fn test_describe_editor_comment_lines() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+
+    // Only lines starting with "JJ: " are comments
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(edit_script, "write\ndescription\nJJ:\nJJ: comment\nJJ:x\n").unwrap();
+    test_env.jj_cmd_ok(&workspace_path, &["describe"]);
+    let stdout = test_env.jj_cmd_success(
+        &workspace_path,
+        &["log", "--no-graph", "-r@", "-T", "description"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    description
+    JJ:
+    JJ:x
+    "###);
+}
+
+#[test]
+fn test_describe_editor_environment() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let workspace_path = test_env.env_root().join("repo");
+    let subdir_path = workspace_path.join("subdir");
+    std::fs::create_dir(&subdir_path).unwrap();
+
+    // The editor runs next to the edited file, with $TERM set if it was unset
+    let edit_script = test_env.set_up_fake_editor();
+    std::fs::write(&edit_script, "dumpcwd cwd\0dumpenv TERM term").unwrap();
+    test_env.jj_cmd_ok(&subdir_path, &["describe"]);
+    let read_dump = |test_env: &TestEnvironment, name: &str| {
+        let dump = std::fs::read_to_string(test_env.env_root().join(name)).unwrap();
+        test_env.normalize_output(&dump)
+    };
+    insta::assert_snapshot!(read_dump(&test_env, "cwd"), @"$TEST_ENV/repo/.jj/repo");
+    insta::assert_snapshot!(read_dump(&test_env, "term"), @"dumb");
+
+    // An existing $TERM is preserved
+    test_env.add_env_var("TERM", "xterm");
+    test_env.jj_cmd_ok(&subdir_path, &["describe"]);
+    insta::assert_snapshot!(read_dump(&test_env, "term"), @"xterm");
+}
+
 #[test]
 fn test_describe_author() {
     let test_env = TestEnvironment::default();
@@ -67,6 +67,114 @@ fn test_edit() {
     "###);
 }
 
+#[test]
+fn test_edit_divergent_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "one"]);
+    test_env.jj_cmd_ok(&repo_path, &["--at-op=@-", "describe", "-m", "two"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "three"]);
+
+    // Divergent change is ambiguous by default
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["edit", "qpvuntsm"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "qpvuntsm" resolved to more than one revision
+    Hint: The revset "qpvuntsm" resolved to these revisions:
+      qpvuntsm?? 66c6502d (empty) two
+      qpvuntsm?? 876f4b7e (empty) one
+    Hint: Some of these commits have the same change id. Abandon one of them with `jj abandon -r <REVISION>`.
+    "###);
+
+    // The latest commit can be picked instead
+    test_env.add_config(r#"revsets.resolve-divergent = "latest""#);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["edit", "qpvuntsm"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm?? 66c6502d (empty) two
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+
+    // Commits of different changes are still ambiguous
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["edit", "description(t)"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "description(t)" resolved to more than one revision
+    Hint: The revset "description(t)" resolved to these revisions:
+      zsuskuln b53e2c3a (empty) three
+      qpvuntsm?? 66c6502d (empty) two
+    "###);
+
+    // Invalid value
+    test_env.add_config(r#"revsets.resolve-divergent = "earliest""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["edit", "qpvuntsm"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `revsets.resolve-divergent`
+    Caused by: enum DivergentResolution does not have variant constructor earliest
+    Hint: Check the `revsets.resolve-divergent` value; expected one of `error` or `latest`.
+    "###);
+}
+
+#[test]
+fn test_edit_divergent_change_latest_by_timestamp() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config(r#"revsets.resolve-divergent = "latest""#);
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "one"]);
+    // The concurrent commit is indexed after "one", but has an older
+    // timestamp
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "--at-op=@-",
+            "--config-toml=debug.commit-timestamp='2001-01-01T00:00:00+00:00'",
+            "describe",
+            "-m",
+            "two",
+        ],
+    );
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "three"]);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r=qpvuntsm",
+            "-T",
+            r#"description.first_line() ++ " " ++ committer.timestamp() ++ "\n""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    two 2001-01-01 00:00:00.000 +00:00
+    one 2001-02-03 04:05:08.000 +07:00
+    "###);
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["edit", "qpvuntsm"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm?? 876f4b7e (empty) one
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_edit_root_commit_unchecked() {
+    use crate::common::{get_stderr_string, strip_last_line};
+
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Rewriting the root commit is a user error, not an internal error
+    let assert = test_env
+        .cargo_bin_cmd("fake-embedder", &repo_path, &["edit-unchecked", "root()"])
+        .assert()
+        .code(1);
+    let stderr = get_stderr_string(&assert);
+    insta::assert_snapshot!(strip_last_line(&stderr), @r###"
+    Error: Cannot rewrite the root commit
+    Hint: The root commit is immutable and can't be rewritten.
+    "###);
+}
+
 #[test]
 // Windows says "Access is denied" when trying to delete the object file.
 #[cfg(unix)]
@@ -49,7 +49,7 @@ fn test_fix_no_config() {
     insta::assert_snapshot!(stderr, @r###"
     Config error: Invalid `fix.tool-command`
     Caused by: configuration property "fix.tool-command" not found
-    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    Hint: Check the `fix.tool-command` value; expected a command string or a list of the command and its arguments.
     "###);
 }
 
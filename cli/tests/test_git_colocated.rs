@@ -363,6 +363,132 @@ fn test_git_colocated_branches() {
     "###);
 }
 
+#[test]
+fn test_git_colocated_auto_import_ref_patterns() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "foo"]);
+    test_env.add_config(r#"git.auto-import-ref-patterns = ["refs/heads/*"]"#);
+
+    // Only the branch matching the patterns is imported automatically
+    let target_id = test_env.jj_cmd_success(
+        &workspace_root,
+        &["log", "--no-graph", "-T=commit_id", "-r=@"],
+    );
+    let oid = Oid::from_str(&target_id).unwrap();
+    git_repo
+        .reference("refs/heads/feature", oid, false, "test")
+        .unwrap();
+    git_repo
+        .reference("refs/remotes/origin/feature", oid, false, "test")
+        .unwrap();
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    feature: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+
+    // `jj git import` imports everything
+    test_env.jj_cmd_ok(&workspace_root, &["git", "import"]);
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    feature: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    feature@origin: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+
+    // Invalid pattern
+    test_env.add_config(r#"git.auto-import-ref-patterns = ["refs/heads/["]"#);
+    let stderr = test_env.jj_cmd_failure(&workspace_root, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Error parsing 'refs/heads/[' for git.auto-import-ref-patterns
+    Caused by: Pattern syntax error near position 11: invalid range pattern
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+}
+
+#[test]
+fn test_git_colocated_auto_import_remotes() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "foo"]);
+    test_env.add_config(r#"git.auto-import-remotes = ["origin"]"#);
+
+    // Only branches of the listed remotes are imported automatically
+    let target_id = test_env.jj_cmd_success(
+        &workspace_root,
+        &["log", "--no-graph", "-T=commit_id", "-r=@"],
+    );
+    let oid = Oid::from_str(&target_id).unwrap();
+    git_repo
+        .reference("refs/heads/feature", oid, false, "test")
+        .unwrap();
+    git_repo
+        .reference("refs/remotes/origin/feature", oid, false, "test")
+        .unwrap();
+    git_repo
+        .reference("refs/remotes/upstream/feature", oid, false, "test")
+        .unwrap();
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    feature: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    feature@origin: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+
+    // `jj git import` imports everything
+    test_env.jj_cmd_ok(&workspace_root, &["git", "import"]);
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    feature: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    feature@origin: rlvkpnrz 1e6f0b40 (empty) foo
+    feature@upstream: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+}
+
+#[test]
+fn test_git_colocated_no_git_export() {
+    let test_env = TestEnvironment::default();
+    let workspace_root = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&workspace_root).unwrap();
+    test_env.jj_cmd_ok(&workspace_root, &["git", "init", "--git-repo", "."]);
+    test_env.jj_cmd_ok(&workspace_root, &["new", "-m", "foo"]);
+
+    // Branches aren't exported with --no-git-export
+    test_env.jj_cmd_ok(
+        &workspace_root,
+        &["branch", "create", "--no-git-export", "foo"],
+    );
+    assert!(git_repo.find_reference("refs/heads/foo").is_err());
+
+    // ... nor with git.auto-export = false
+    test_env.add_config("git.auto-export = false");
+    test_env.jj_cmd_ok(&workspace_root, &["branch", "create", "bar"]);
+    assert!(git_repo.find_reference("refs/heads/bar").is_err());
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: rlvkpnrz 1e6f0b40 (empty) foo
+    foo: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+
+    // `jj git export` exports them
+    test_env.jj_cmd_ok(&workspace_root, &["git", "export"]);
+    assert!(git_repo.find_reference("refs/heads/foo").is_ok());
+    assert!(git_repo.find_reference("refs/heads/bar").is_ok());
+    let stdout = get_branch_output(&test_env, &workspace_root);
+    insta::assert_snapshot!(stdout, @r###"
+    bar: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    foo: rlvkpnrz 1e6f0b40 (empty) foo
+      @git: rlvkpnrz 1e6f0b40 (empty) foo
+    "###);
+}
+
 #[test]
 fn test_git_colocated_branch_forget() {
     let test_env = TestEnvironment::default();
@@ -642,6 +768,61 @@ fn test_git_colocated_external_checkout() {
     "###);
 }
 
+#[test]
+fn test_git_colocated_external_checkout_wc_parent() {
+    let test_env = TestEnvironment::default();
+    let repo_path = test_env.env_root().join("repo");
+    let git_repo = git2::Repository::init(&repo_path).unwrap();
+    let git_check_out_ref = |name| {
+        git_repo
+            .set_head_detached(git_repo.find_reference(name).unwrap().target().unwrap())
+            .unwrap()
+    };
+
+    test_env.jj_cmd_ok(&repo_path, &["git", "init", "--git-repo=."]);
+    test_env.jj_cmd_ok(&repo_path, &["ci", "-m=A"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-r@-", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m=B", "root()"]);
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "b"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "a", "-m=wc"]);
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "add", "../secondary"]);
+    let secondary_path = test_env.env_root().join("secondary");
+
+    // Import a different Git HEAD from the non-colocated workspace, so the
+    // recorded HEAD@git no longer matches the working-copy parent
+    git_check_out_ref("refs/heads/b");
+    test_env.jj_cmd_ok(&secondary_path, &["git", "import"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉  8bb9e8d42a37c2a4e8dcfad97fce0b8f49bc7afa
+    │ @  be4094df71f7d4229558395091b696f07e710b15 wc
+    ├─╯
+    ◉  a7e4cec4256b7995129b9d1e1bda7e1df6e60678 a A
+    │ ◉  eccedddfa5152d99fc8ddd1081b375387a8a382a b HEAD@git B
+    ├─╯
+    ◉  0000000000000000000000000000000000000000
+    "###);
+
+    // Detach the Git HEAD at the working-copy parent. The working-copy commit
+    // and its changes should be kept.
+    git_check_out_ref("refs/heads/a");
+    let (stdout, stderr) = get_log_output_with_stderr(&test_env, &repo_path);
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  8bb9e8d42a37c2a4e8dcfad97fce0b8f49bc7afa
+    │ @  be4094df71f7d4229558395091b696f07e710b15 wc
+    ├─╯
+    ◉  a7e4cec4256b7995129b9d1e1bda7e1df6e60678 a HEAD@git A
+    │ ◉  eccedddfa5152d99fc8ddd1081b375387a8a382a b B
+    ├─╯
+    ◉  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(stderr, @"");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["diff", "--summary"]);
+    insta::assert_snapshot!(stdout, @r###"
+    A file
+    "###);
+}
+
 #[test]
 fn test_git_colocated_squash_undo() {
     let test_env = TestEnvironment::default();
@@ -372,6 +372,49 @@ fn test_git_init_colocated_via_git_repo_path_symlink_gitlink() {
     "###);
 }
 
+#[test]
+fn test_git_init_colocated_many_untracked_remote_branches() {
+    let test_env = TestEnvironment::default();
+    test_env.add_config("git.auto-local-branch = false");
+    test_env.add_config("ui.hint-list-limit = 2");
+
+    // Set up remote refs
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "remote"]);
+    let remote_path = test_env.env_root().join("remote");
+    test_env.jj_cmd_ok(&remote_path, &["branch", "create", "a", "b", "c"]);
+    test_env.jj_cmd_ok(&remote_path, &["new"]);
+    test_env.jj_cmd_ok(&remote_path, &["git", "export"]);
+
+    // Set up local branches of the same names
+    let remote_git_path = remote_path.join(PathBuf::from_iter([".jj", "repo", "store", "git"]));
+    let local_path = test_env.env_root().join("local");
+    let git_repo = git2::Repository::clone(remote_git_path.to_str().unwrap(), &local_path).unwrap();
+    for name in ["a", "b", "c"] {
+        let git_ref = git_repo
+            .find_reference(&format!("refs/remotes/origin/{name}"))
+            .unwrap();
+        git_repo
+            .reference(
+                &format!("refs/heads/{name}"),
+                git_ref.target().unwrap(),
+                false,
+                "",
+            )
+            .unwrap();
+    }
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&local_path, &["git", "init", "--git-repo=."]);
+    insta::assert_snapshot!(stderr, @r###"
+    Done importing changes from the underlying Git repo.
+    Hint: The following remote branches aren't associated with the existing local branches:
+      a@origin
+      b@origin
+      ... and 1 more
+    Hint: Run `jj branch track 'glob:*@origin'` to keep all branches of the remote updated on future pulls.
+    Initialized repo in "."
+    "###);
+}
+
 #[test]
 fn test_git_init_colocated_via_git_repo_path_imported_refs() {
     let test_env = TestEnvironment::default();
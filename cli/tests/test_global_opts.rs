@@ -14,7 +14,7 @@
 
 use std::ffi::OsString;
 
-use crate::common::{get_stderr_string, strip_last_line, TestEnvironment};
+use crate::common::{get_stderr_string, get_stdout_string, strip_last_line, TestEnvironment};
 
 #[test]
 fn test_non_utf8_arg() {
@@ -63,7 +63,7 @@ fn test_no_subcommand() {
     let repo_path = test_env.env_root().join("repo");
 
     // Outside of a repo.
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &[]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &[]);
     insta::assert_snapshot!(stderr, @r###"
     Hint: Use `jj -h` for a list of available commands.
     Run `jj config set --user ui.default-command log` to disable this message.
@@ -71,7 +71,7 @@ fn test_no_subcommand() {
     "###);
 
     test_env.add_config(r#"ui.default-command="log""#);
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &[]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &[]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -114,6 +114,47 @@ fn test_no_subcommand() {
     "###);
 }
 
+#[test]
+fn test_unknown_subcommand() {
+    let test_env = TestEnvironment::default();
+
+    let stderr = test_env.jj_cmd_cli_error(test_env.env_root(), &["lgo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: unrecognized subcommand 'lgo'
+
+    Usage: jj [OPTIONS] <COMMAND>
+
+    For more information, try '--help'.
+    Hint: Use `jj -h` for a list of available commands.
+    "###);
+
+    // clap suggests similar built-in commands
+    let stderr = test_env.jj_cmd_cli_error(test_env.env_root(), &["lof"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: unrecognized subcommand 'lof'
+
+      tip: a similar subcommand exists: 'log'
+
+    Usage: jj [OPTIONS] <COMMAND>
+
+    For more information, try '--help'.
+    Hint: Use `jj -h` for a list of available commands.
+    "###);
+
+    // Aliases are suggested, too
+    test_env.add_config(r#"aliases.lgg = ["log"]"#);
+    let stderr = test_env.jj_cmd_cli_error(test_env.env_root(), &["lgo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: unrecognized subcommand 'lgo'
+
+    Usage: jj [OPTIONS] <COMMAND>
+
+    For more information, try '--help'.
+    Hint: Did you mean "lgg"?
+    Hint: Use `jj -h` for a list of available commands.
+    "###);
+}
+
 #[test]
 fn test_ignore_working_copy() {
     let test_env = TestEnvironment::default();
@@ -148,7 +189,7 @@ fn test_ignore_working_copy() {
 #[test]
 fn test_repo_arg_with_init() {
     let test_env = TestEnvironment::default();
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["init", "-R=.", "repo"]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &["init", "-R=.", "repo"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -157,7 +198,8 @@ fn test_repo_arg_with_init() {
 #[test]
 fn test_repo_arg_with_git_clone() {
     let test_env = TestEnvironment::default();
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["git", "clone", "-R=.", "remote"]);
+    let stderr =
+        test_env.jj_cmd_no_workspace(test_env.env_root(), &["git", "clone", "-R=.", "remote"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -180,7 +222,7 @@ fn test_resolve_workspace_directory() {
     "###);
 
     // Explicit subdirectory path
-    let stderr = test_env.jj_cmd_failure(&subdir, &["status", "-R", "."]);
+    let stderr = test_env.jj_cmd_no_workspace(&subdir, &["status", "-R", "."]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -194,30 +236,91 @@ fn test_resolve_workspace_directory() {
     "###);
 
     // "../../..".ancestors() contains "../..", but it should never be looked up.
-    let stderr = test_env.jj_cmd_failure(&subdir, &["status", "-R", "../../.."]);
+    let stderr = test_env.jj_cmd_no_workspace(&subdir, &["status", "-R", "../../.."]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "../../.."
     "###);
 }
 
+#[test]
+fn test_resolve_workspace_directory_jj_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let subdir = repo_path.join("dir");
+    std::fs::create_dir(&subdir).unwrap();
+
+    // .jj can be a file pointing to the relocated directory
+    std::fs::rename(repo_path.join(".jj"), test_env.env_root().join("jj-dir")).unwrap();
+    std::fs::write(repo_path.join(".jj"), "../jj-dir\n").unwrap();
+    std::fs::write(subdir.join("file"), "contents").unwrap();
+    let stdout = test_env.jj_cmd_success(&subdir, &["status"]);
+    insta::assert_snapshot!(stdout, @r###"
+    Working copy changes:
+    A file
+    Working copy : qpvuntsm 142bde1e (no description set)
+    Parent commit: zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+
+    // Error if the pointed directory doesn't exist
+    std::fs::write(repo_path.join(".jj"), "../missing").unwrap();
+    let stderr = test_env.jj_cmd_no_workspace(&subdir, &["status"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: There is no jj repo in "."
+    "###);
+}
+
+#[test]
+fn test_resolve_workspace_directory_expanded() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.home_dir(), &["git", "init", "repo"]);
+
+    // "~" is expanded even if the shell didn't
+    let stdout = test_env.jj_cmd_success(test_env.env_root(), &["log", "-R", "~/repo", "-r@"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 230dd059
+    │  (empty) (no description set)
+    ~
+    "###);
+
+    // So are environment variables
+    let assert = test_env
+        .jj_cmd(test_env.env_root(), &["log", "-R", "${REPO_DIR}", "-r@"])
+        .env("REPO_DIR", test_env.home_dir().join("repo"))
+        .assert()
+        .success();
+    insta::assert_snapshot!(get_stdout_string(&assert), @r###"
+    @  qpvuntsm test.user@example.com 2001-02-03 08:05:07 230dd059
+    │  (empty) (no description set)
+    ~
+    "###);
+
+    // Unset variables are kept as is
+    let stderr =
+        test_env.jj_cmd_no_workspace(test_env.env_root(), &["status", "-R", "$UNSET_DIR/repo"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: There is no jj repo in "$UNSET_DIR/repo"
+    "###);
+}
+
 #[test]
 fn test_no_workspace_directory() {
     let test_env = TestEnvironment::default();
     let repo_path = test_env.env_root().join("repo");
     std::fs::create_dir(&repo_path).unwrap();
 
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["status"]);
+    let stderr = test_env.jj_cmd_no_workspace(&repo_path, &["status"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     "###);
 
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["status", "-R", "repo"]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &["status", "-R", "repo"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "repo"
     "###);
 
     std::fs::create_dir(repo_path.join(".git")).unwrap();
-    let stderr = test_env.jj_cmd_failure(&repo_path, &["status"]);
+    let stderr = test_env.jj_cmd_no_workspace(&repo_path, &["status"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: There is no jj repo in "."
     Hint: It looks like this is a git repo. You can create a jj repo backed by it by running this:
@@ -299,6 +402,18 @@ fn test_broken_repo_structure() {
     let store_path = repo_path.join(".jj").join("repo").join("store");
     let store_type_path = store_path.join("type");
 
+    // Test the error message when the working copy is of unknown type.
+    let working_copy_type_path = repo_path.join(".jj").join("working_copy").join("type");
+    std::fs::write(&working_copy_type_path, "unknown").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: This version of the jj binary doesn't support this type of working copy
+    Caused by: Unsupported working copy backend type 'unknown'
+    Hint: Supported working copy types: local
+    Hint: The workspace may have been created by a newer or custom build of jj. Try upgrading jj.
+    "###);
+    std::fs::write(&working_copy_type_path, "local").unwrap();
+
     // Test the error message when the git repository can't be located.
     std::fs::remove_file(store_path.join("git_target")).unwrap();
     let stderr = test_env.jj_cmd_internal_error(&repo_path, &["log"]);
@@ -310,10 +425,11 @@ fn test_broken_repo_structure() {
 
     // Test the error message when the commit backend is of unknown type.
     std::fs::write(&store_type_path, "unknown").unwrap();
-    let stderr = test_env.jj_cmd_internal_error(&repo_path, &["log"]);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log"]);
     insta::assert_snapshot!(stderr, @r###"
-    Internal error: This version of the jj binary doesn't support this type of repo
+    Error: This version of the jj binary doesn't support this type of repo
     Caused by: Unsupported commit backend type 'unknown'
+    Hint: The repo may have been created by a newer or custom build of jj. Try upgrading jj.
     "###);
 
     // Test the error message when the file indicating the commit backend type
@@ -412,7 +528,7 @@ fn test_color_config() {
     "###);
 
     // Test that NO_COLOR does NOT override the request for color in the config file
-    test_env.add_env_var("NO_COLOR", "");
+    test_env.add_env_var("NO_COLOR", "1");
     let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "commit_id"]);
     insta::assert_snapshot!(stdout, @r###"
     @  [38;5;4m230dd059e1b059aefc0da06a2e5a7dbf22362f22[39m
@@ -432,6 +548,45 @@ fn test_color_config() {
     "###);
 }
 
+#[test]
+fn test_color_env_vars() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Empty CLICOLOR_FORCE is ignored
+    test_env.add_env_var("CLICOLOR_FORCE", "");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ◉  0000000000000000000000000000000000000000
+    "###);
+
+    // CLICOLOR_FORCE turns color on even if NO_COLOR is set
+    test_env.add_env_var("CLICOLOR_FORCE", "1");
+    test_env.add_env_var("NO_COLOR", "1");
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  [38;5;4m230dd059e1b059aefc0da06a2e5a7dbf22362f22[39m
+    ◉  [38;5;4m0000000000000000000000000000000000000000[39m
+    "###);
+
+    // --color and the config override CLICOLOR_FORCE
+    let stdout = test_env.jj_cmd_success(&repo_path, &["--color=never", "log", "-T", "commit_id"]);
+    insta::assert_snapshot!(stdout, @r###"
+    @  230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ◉  0000000000000000000000000000000000000000
+    "###);
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["--config-toml=ui.color='never'", "log", "-T", "commit_id"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @  230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ◉  0000000000000000000000000000000000000000
+    "###);
+}
+
 #[test]
 fn test_color_ui_messages() {
     let test_env = TestEnvironment::default();
@@ -440,7 +595,7 @@ fn test_color_ui_messages() {
     test_env.add_config("ui.color = 'always'");
 
     // hint and error
-    let stderr = test_env.jj_cmd_failure(test_env.env_root(), &["-R."]);
+    let stderr = test_env.jj_cmd_no_workspace(test_env.env_root(), &["-R."]);
     insta::assert_snapshot!(stderr, @r###"
     [1m[38;5;6mHint: [0m[39mUse `jj -h` for a list of available commands.[39m
     [39mRun `jj config set --user ui.default-command log` to disable this message.[39m
@@ -509,6 +664,64 @@ fn test_quiet() {
     insta::assert_snapshot!(stderr, @"");
 }
 
+#[test]
+fn test_wrap_errors() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    test_env.add_config("ui.allow-filesets = true");
+    let render = |wrap: bool| {
+        let assert = test_env
+            .jj_cmd(
+                test_env.env_root(),
+                &[
+                    &format!("--config-toml=ui.wrap-errors={wrap}"),
+                    "file",
+                    "show",
+                    "-Rrepo",
+                    "out",
+                ],
+            )
+            .env("COLUMNS", "30")
+            .assert()
+            .failure();
+        get_stderr_string(&assert).replace('\\', "/")
+    };
+
+    insta::assert_snapshot!(render(false), @r###"
+    Error: Failed to parse fileset: Invalid file pattern
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | out
+      | ^-^
+      |
+      = Invalid file pattern
+    2: Path "out" is not in the repo "repo"
+    3: Invalid component ".." in repo-relative path "../out"
+    Hint: Consider using root:"out" to specify repo-relative path
+    "###);
+
+    // Long lines are wrapped, but the existing line breaks are preserved
+    insta::assert_snapshot!(render(true), @r###"
+    Error: Failed to parse
+    fileset: Invalid file pattern
+    Caused by:
+    1:  --> 1:1
+      |
+    1 | out
+      | ^-^
+      |
+      = Invalid file pattern
+    2: Path "out" is not in the
+    repo "repo"
+    3: Invalid component ".." in
+    repo-relative path "../out"
+    Hint: Consider using
+    root:"out" to specify
+    repo-relative path
+    "###);
+}
+
 #[test]
 fn test_early_args() {
     // Test that help output parses early args
@@ -532,6 +745,29 @@ fn test_early_args() {
     );
 }
 
+#[test]
+fn test_config_toml_unknown_key() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["log", "--config-toml=uii.color='always'\nfoo=1", "-r@"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Unknown top-level config key `foo` in --config-toml argument
+    Warning: Unknown top-level config key `uii` in --config-toml argument
+    "###);
+
+    // Known keys are accepted silently
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["log", "--config-toml=ui.color='never'", "-r@"],
+    );
+    insta::assert_snapshot!(stderr, @"");
+}
+
 #[test]
 fn test_invalid_config() {
     // Test that we get a reasonable error if the config is invalid (#55)
@@ -598,15 +834,46 @@ fn test_help() {
       -h, --help                 Print help (see more with '--help')
 
     Global Options:
-      -R, --repository <REPOSITORY>      Path to repository to operate on
-          --ignore-working-copy          Don't snapshot the working copy, and don't update it
-          --ignore-immutable             Allow rewriting immutable commits
-          --at-operation <AT_OPERATION>  Operation to load the repo at [default: @] [aliases: at-op]
-          --debug                        Enable debug logging
-          --color <WHEN>                 When to colorize output (always, never, debug, auto)
-          --quiet                        Silence non-primary command output
-          --no-pager                     Disable the pager
-          --config-toml <TOML>           Additional configuration options (can be repeated)
+      -R, --repository <REPOSITORY>
+              Path to repository to operate on
+          --ignore-working-copy
+              Don't snapshot the working copy, and don't update it
+          --allow-stale-working-copy
+              Load the repo at the working copy's operation if the working copy is stale
+          --ignore-immutable
+              Allow rewriting immutable commits
+          --no-git-export
+              Don't export refs to Git automatically in a co-located repo
+          --at-operation <AT_OPERATION>
+              Operation to load the repo at [default: @] [aliases: at-op]
+          --revset-alias <NAME=DEFINITION>
+              Define a revset alias for this command only (can be repeated)
+          --template-alias <NAME=DEFINITION>
+              Define a template alias for this command only (can be repeated)
+          --debug
+              Enable debug logging
+          --debug-op-ids
+              Print the operation IDs before and after each mutating command
+          --fail-on-no-change
+              Exit with status 4 if a mutating command didn't change anything
+          --color <WHEN>
+              When to colorize output (always, never, debug, auto)
+          --quiet
+              Silence non-primary command output
+          --yes
+              Answer yes to all confirmation prompts
+          --no-pager
+              Disable the pager
+          --no-user-config
+              Don't load the user configuration
+          --sign-with <BACKEND>
+              Sign commits created by this command with the given backend
+          --no-sign
+              Don't sign commits created by this command
+          --config-toml <TOML>
+              Additional configuration options (can be repeated)
+          --config-file <PATH>
+              Additional configuration files to load (can be repeated)
     "###);
 }
 
@@ -628,3 +895,251 @@ fn test_debug_logging_enabled() {
     // Luckily, insta will print this in colour when reviewing.
     insta::assert_snapshot!(log_line, @"[32m INFO[0m [2mjj_cli::cli_util[0m[2m:[0m debug logging enabled");
 }
+
+#[test]
+fn test_debug_internal_error_backtrace() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let views_dir = repo_path.join(".jj/repo/op_store/views");
+    for entry in std::fs::read_dir(views_dir).unwrap() {
+        std::fs::remove_file(entry.unwrap().path()).unwrap();
+    }
+
+    // Internal errors don't include a backtrace by default
+    let assert = test_env.jj_cmd(&repo_path, &["log"]).assert().code(255);
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    assert!(stderr.starts_with("Internal error: Failed to load the repo"));
+    assert!(!stderr.contains("Backtrace:"));
+
+    // --debug captures the backtrace of the internal error
+    let assert = test_env
+        .jj_cmd(&repo_path, &["log", "--debug"])
+        .assert()
+        .code(255);
+    let stderr = get_stderr_string(&assert);
+    assert!(stderr.contains("Internal error: Failed to load the repo"));
+    assert!(stderr.contains("Backtrace:"));
+}
+
+#[test]
+fn test_span_timings() {
+    let mut test_env = TestEnvironment::default();
+    test_env.add_env_var("JJ_TIME", "1");
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(test_env.env_root(), &["version"]);
+    assert!(stdout.starts_with("jj "));
+    let mut lines = stderr.lines();
+    insta::assert_snapshot!(lines.next().unwrap(), @"Time spent in spans:");
+    assert!(lines.any(|line| line.ends_with("x  jj_cli::cli_util::run_internal")));
+}
+
+#[test]
+fn test_debug_op_ids() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo", "--debug-op-ids"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Base operation: b51416386f2685fd5493f2b20e8eec3c24a1776d9e1a7cb5ed7e30d2d9c88c0c1e1fe71b0b7358cba60de42533d1228ed9878f2f89817d892c803395ccf9fe92
+    New operation: 1c86554cd3597529b76ede786b2cf2490d1f8ca99ea7daabf182430c02747aad2b7b5506be847804839dc6986f0fc886dd9a3e44ca32d4d431d83e2e3fcf485e
+    Working copy now at: qpvuntsm 280394c7 (empty) foo
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+
+    // Nothing is printed if no operation was created
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo", "--debug-op-ids"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+}
+
+#[test]
+fn test_no_user_config() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(
+        r#"
+        aliases.user-alias = ["log"]
+        templates.log = '"user template\n"'
+        "#,
+    );
+    let repo_config_path = repo_path.join(".jj/repo/config.toml");
+    std::fs::write(&repo_config_path, r#"revsets.log = "root()""#).unwrap();
+
+    let stdout = test_env.jj_cmd_success(&repo_path, &["user-alias", "--no-graph"]);
+    insta::assert_snapshot!(stdout, @"user template");
+
+    // The user config is ignored, but the repo config is still loaded
+    let stdout = test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "--no-user-config"]);
+    insta::assert_snapshot!(stdout, @"zzzzzzzz root() 00000000");
+    let stderr = test_env.jj_cmd_cli_error(&repo_path, &["user-alias", "--no-user-config"]);
+    insta::assert_snapshot!(stderr, @r###"
+    error: unrecognized subcommand 'user-alias'
+
+    Usage: jj [OPTIONS] <COMMAND>
+
+    For more information, try '--help'.
+    Hint: Use `jj -h` for a list of available commands.
+    "###);
+
+    // --config-toml still applies
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "--no-user-config",
+            "--config-toml=templates.log='\"arg template\\n\"'",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"arg template");
+}
+
+#[test]
+fn test_sign_with() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // Unknown backend is an error
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["describe", "-m", "foo", "--sign-with", "unknown"],
+    );
+    insta::assert_snapshot!(stderr, @"Error: Unknown signing backend configured: unknown");
+
+    // --no-sign overrides the configured backend
+    test_env.add_config(r#"signing.backend = "unknown""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "foo"]);
+    insta::assert_snapshot!(stderr, @"Error: Unknown signing backend configured: unknown");
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo", "--no-sign"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm 25c5a749 (empty) foo
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+
+    // The options can't be combined
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["describe", "-m", "foo", "--sign-with", "ssh", "--no-sign"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--sign-with <BACKEND>' cannot be used with '--no-sign'
+
+    Usage: jj describe --message <MESSAGE> --sign-with <BACKEND> [REVISION]
+
+    For more information, try '--help'.
+    "###);
+}
+
+#[test]
+fn test_fail_on_no_change() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo"]);
+
+    // Without the flag, a no-op command succeeds
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "foo"]);
+    insta::assert_snapshot!(stderr, @"Nothing changed.");
+
+    let assert = test_env
+        .jj_cmd(
+            &repo_path,
+            &["describe", "-m", "foo", "--fail-on-no-change"],
+        )
+        .assert()
+        .code(4);
+    insta::assert_snapshot!(get_stderr_string(&assert), @"Nothing changed.");
+
+    // A command going through a transaction which turns out to be empty
+    let assert = test_env
+        .jj_cmd(&repo_path, &["git", "import", "--fail-on-no-change"])
+        .assert()
+        .code(4);
+    insta::assert_snapshot!(get_stderr_string(&assert), @"Nothing changed.");
+
+    // Nothing happens if something changed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "bar", "--fail-on-no-change"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm 2fd64adf (empty) bar
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    "###);
+}
+
+#[test]
+fn test_workspace_search_ceiling() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let sub_path = repo_path.join("sub");
+    let nested_path = sub_path.join("dir");
+    std::fs::create_dir_all(&nested_path).unwrap();
+
+    // The search goes up to the root by default
+    let stdout = test_env.jj_cmd_success(&nested_path, &["root"]);
+    insta::assert_snapshot!(stdout, @"$TEST_ENV/repo");
+
+    // The search doesn't go up into the ceiling directory
+    test_env.add_env_var("JJ_CEILING_DIRECTORIES", sub_path.to_str().unwrap());
+    let stderr = test_env.jj_cmd_no_workspace(&nested_path, &["root"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: There is no jj repo in "."
+    "###);
+    test_env.add_env_var("JJ_CEILING_DIRECTORIES", "");
+
+    // Ceiling directories can also be configured
+    test_env.add_config(&format!(
+        "workspace.search-ceiling = ['{}']",
+        repo_path.to_str().unwrap()
+    ));
+    let stderr = test_env.jj_cmd_no_workspace(&sub_path, &["root"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: There is no jj repo in "."
+    "###);
+
+    // The current directory is searched even if it's a ceiling directory
+    let stdout = test_env.jj_cmd_success(&repo_path, &["root"]);
+    insta::assert_snapshot!(stdout, @"$TEST_ENV/repo");
+}
+
+#[test]
+fn test_trace_dir() {
+    let mut test_env = TestEnvironment::default();
+    let trace_dir = test_env.env_root().join("traces");
+    test_env.add_env_var("JJ_TRACE", "trace.json");
+    test_env.add_env_var("JJ_TRACE_DIR", trace_dir.to_str().unwrap());
+
+    // The directory is created if needed
+    let (_stdout, stderr) = test_env.jj_cmd_ok(test_env.env_root(), &["version"]);
+    insta::assert_snapshot!(stderr, @"");
+    assert!(trace_dir.join("trace.json").is_file());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_trace_dir_fallback() {
+    let mut test_env = TestEnvironment::default();
+    let temp_dir = test_env.env_root().join("temp");
+    std::fs::create_dir(&temp_dir).unwrap();
+    // A file can't be used as the directory
+    let trace_dir = test_env.env_root().join("file");
+    std::fs::write(&trace_dir, "").unwrap();
+    test_env.add_env_var("TMPDIR", temp_dir.to_str().unwrap());
+    test_env.add_env_var("JJ_TRACE", "trace.json");
+    test_env.add_env_var("JJ_TRACE_DIR", trace_dir.to_str().unwrap());
+
+    let (_stdout, stderr) = test_env.jj_cmd_ok(test_env.env_root(), &["version"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: Failed to create JJ_TRACE_DIR $TEST_ENV/file: File exists (os error 17). Writing the trace to $TEST_ENV/temp instead.
+    "###);
+    assert!(temp_dir.join("trace.json").is_file());
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::TestEnvironment;
+use crate::common::{get_stderr_string, TestEnvironment};
 
 #[test]
 fn test_rewrite_immutable_generic() {
@@ -101,6 +101,42 @@ fn test_rewrite_immutable_generic() {
     insta::assert_snapshot!(stderr, @r###"
     Error: The root commit 000000000000 is immutable
     "###);
+
+    // `jj status` also reports an immutable_heads() that can't be resolved
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "branch_that_does_not_exist""#);
+    let assert = test_env.jj_cmd(&repo_path, &["status"]).assert().code(1);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Config error: Invalid `revset-aliases.immutable_heads()`
+    Caused by: Revision "branch_that_does_not_exist" doesn't exist
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+
+    // Error mutating the repo if immutable_heads() references an undefined
+    // alias
+    test_env.add_config("revsets.short-prefixes = ''");
+    test_env.add_config(r#"revset-aliases."immutable_heads()" = "undefined_alias()""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["new", "main"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `revset-aliases.immutable_heads()`
+    Caused by:  --> 1:1
+      |
+    1 | undefined_alias()
+      | ^-------------^
+      |
+      = Function "undefined_alias" doesn't exist
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+    let assert = test_env.jj_cmd(&repo_path, &["status"]).assert().code(1);
+    insta::assert_snapshot!(get_stderr_string(&assert), @r###"
+    Config error: Invalid `revset-aliases.immutable_heads()`
+    Caused by:  --> 1:1
+      |
+    1 | undefined_alias()
+      | ^-------------^
+      |
+      = Function "undefined_alias" doesn't exist
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
 }
 
 #[test]
@@ -57,6 +57,62 @@ fn test_log_with_no_template() {
     "###);
 }
 
+#[test]
+fn test_log_template_file() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "first"]);
+
+    let template_path = test_env.env_root().join("template");
+    std::fs::write(
+        &template_path,
+        "separate(' ', change_id.short(), description.first_line()) ++ \"\\n\"",
+    )
+    .unwrap();
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "--template-file", "../template"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    qpvuntsmwlqt first
+    zzzzzzzzzzzz
+    "###);
+
+    // Missing file
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--template-file", "../missing"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to read template file ../missing
+    Caused by: No such file or directory (os error 2)
+    "###);
+
+    // Parse error
+    std::fs::write(&template_path, "unknown_keyword").unwrap();
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--template-file", "../template"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to parse template: Keyword "unknown_keyword" doesn't exist
+    Caused by:  --> 1:1
+      |
+    1 | unknown_keyword
+      | ^-------------^
+      |
+      = Keyword "unknown_keyword" doesn't exist
+    "###);
+
+    // Can't be combined with -T
+    let stderr = test_env.jj_cmd_cli_error(
+        &repo_path,
+        &["log", "-T", "description", "--template-file", "../template"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    error: the argument '--template <TEMPLATE>' cannot be used with '--template-file <PATH>'
+
+    Usage: jj log --template <TEMPLATE> [PATHS]...
+
+    For more information, try '--help'.
+    "###);
+}
+
 #[test]
 fn test_log_with_or_without_diff() {
     let test_env = TestEnvironment::default();
@@ -145,6 +145,16 @@ fn test_new_merge() {
     Parent commit      : wqnwkozp 11402323 (empty) (no description set)
     "###);
 
+    // --all allows any number of revisions in each revset
+    test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["new", "--all", "@", "visible_heads()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: nmzmmopx eda8b2c3 (empty) (no description set)
+    Parent commit      : wqnwkozp 11402323 (empty) (no description set)
+    "###);
+
     // merge with root
     let stderr = test_env.jj_cmd_failure(&repo_path, &["new", "@", "root()"]);
     insta::assert_snapshot!(stderr, @r###"
@@ -71,6 +71,16 @@ fn test_op_log() {
         test_env.jj_cmd_failure(&repo_path, &["log", "--at-op", "@----"]), @r###"
     Error: The "@----" expression resolved to no operations
     "###);
+    // Operation can be resolved by time
+    insta::assert_snapshot!(
+        get_log_output(&test_env, &repo_path, "@{2001-02-03T04:05:07+07:00}"), @r###"
+    @  230dd059e1b059aefc0da06a2e5a7dbf22362f22
+    ◉  0000000000000000000000000000000000000000
+    "###);
+    insta::assert_snapshot!(
+        test_env.jj_cmd_failure(&repo_path, &["log", "--at-op", "@{2001-02-01}"]), @r###"
+    Error: No operation found at or before "@{2001-02-01}"
+    "###);
 
     // We get a reasonable message if an invalid operation ID is specified
     insta::assert_snapshot!(test_env.jj_cmd_failure(&repo_path, &["log", "--at-op", "foo"]), @r###"
@@ -93,6 +103,23 @@ fn test_op_log() {
     "###);
 }
 
+#[test]
+fn test_op_log_ambiguous_id_prefix() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    for i in 0..12 {
+        test_env.jj_cmd_ok(&repo_path, &["describe", "-m", &format!("description {i}")]);
+    }
+
+    // Two of the operation IDs share the prefix "5"
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "--at-op", "5"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Operation ID prefix "5" is ambiguous
+    Hint: Did you mean "5f0016c842cf", "5931f9738158"?
+    "###);
+}
+
 #[test]
 fn test_op_log_with_custom_symbols() {
     let test_env = TestEnvironment::default();
@@ -353,6 +380,36 @@ fn test_op_log_configurable() {
     assert!(stdout.contains("my-username@my-hostname"));
 }
 
+#[test]
+fn test_op_log_record_display_args() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("operation.record-display-args = true");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "my message"]);
+
+    // Tags are stored in a hash map, so sort them for stable output
+    let stdout = test_env.jj_cmd_success(&repo_path, &["op", "log", "-n1", "--no-graph", "-Ttags"]);
+    insta::assert_snapshot!(stdout.lines().sorted().join("\n"), @r###"
+    args: jj describe -m 'my message'
+    args_display: jj describe -m my message
+    "###);
+}
+
+#[test]
+fn test_op_log_record_display_args_invalid() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config(r#"operation.record-display-args = "maybe""#);
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["describe", "-m", "my message"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: invalid type: string "maybe", expected a boolean
+    For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
+    "###);
+}
+
 #[test]
 fn test_op_abandon_ancestors() {
     let test_env = TestEnvironment::default();
@@ -507,6 +564,49 @@ fn test_op_abandon_without_updating_working_copy() {
     "###);
 }
 
+#[test]
+fn test_post_operation_hook() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let formatter_path = assert_cmd::cargo::cargo_bin("fake-formatter");
+    let escaped_formatter_path = formatter_path.to_str().unwrap().replace('\\', r"\\");
+
+    // The hook's output goes to stderr
+    let hook_config =
+        format!(r#"hooks.post-operation = ["{escaped_formatter_path}", "--stdout", "hook ran\n"]"#);
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "first", "--config-toml", &hook_config],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm fa15625b (empty) first
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    hook ran
+    "###);
+
+    // Commands that don't record an operation don't run the hook
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["log", "--config-toml", &hook_config]);
+    insta::assert_snapshot!(stderr, @"");
+
+    // A failing hook doesn't fail the command
+    let hook_config =
+        format!(r#"hooks.post-operation = ["{escaped_formatter_path}", "--fail", "--stdout", ""]"#);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["describe", "-m", "second", "--config-toml", &hook_config],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: qpvuntsm d60ed05a (empty) second
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    Warning: Post-operation hook exited with exit status: 1
+    "###);
+    let stdout =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tdescription"]);
+    insta::assert_snapshot!(stdout, @"second");
+}
+
 fn get_log_output(test_env: &TestEnvironment, repo_path: &Path, op_id: &str) -> String {
     test_env.jj_cmd_success(
         repo_path,
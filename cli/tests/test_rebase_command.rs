@@ -783,6 +783,18 @@ fn test_rebase_multiple_destinations() {
     ◉
     "###);
 
+    // undo and do it again, but with --all
+    let (_, _) = test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    let (_, _) = test_env.jj_cmd_ok(&repo_path, &["rebase", "--all", "-r=a", "-d=b|c"]);
+    insta::assert_snapshot!(get_log_output(&test_env, &repo_path), @r###"
+    ◉    a
+    ├─╮
+    │ ◉  b
+    @ │  c
+    ├─╯
+    ◉
+    "###);
+
     let stderr = test_env.jj_cmd_failure(&repo_path, &["rebase", "-r", "a", "-d", "b", "-d", "b"]);
     insta::assert_snapshot!(stderr, @r###"
     Error: More than one revset resolved to revision d370aee184ba
@@ -103,6 +103,102 @@ fn test_report_conflicts() {
     "###);
 }
 
+#[test]
+fn test_report_conflicts_truncated() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.max-conflict-report = 1");
+
+    std::fs::write(repo_path.join("file"), "A\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=A"]);
+    std::fs::write(repo_path.join("file"), "B\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=B"]);
+    std::fs::write(repo_path.join("file"), "C\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=C"]);
+
+    // The hint is computed from all conflicted commits
+    let (stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["rebase", "-r=description(B)", "-d=root()"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits onto destination
+    Rebased 2 descendant commits
+    New conflicts appeared in these commits:
+      kkmpptxz 72cdfc5f (conflict) C
+      ... and 1 more
+    To resolve the conflicts, start by updating to one of the first ones:
+      jj new kkmpptxzrspx
+      jj new rlvkpnrzqnoo
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    Working copy now at: zsuskuln cf151abc (conflict) (empty) (no description set)
+    Parent commit      : kkmpptxz 72cdfc5f (conflict) C
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    2-sided conflict
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["undo"]);
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Existing conflicts were resolved or abandoned from these commits:
+      kkmpptxz hidden 72cdfc5f (conflict) C
+      ... and 1 more
+    Working copy now at: zsuskuln b0288c0b (empty) (no description set)
+    Parent commit      : kkmpptxz 782dc1ce C
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+}
+
+#[test]
+fn test_report_total_conflicts() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.add_config("ui.report-total-conflicts = true");
+
+    std::fs::write(repo_path.join("file"), "A\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["commit", "-m=A"]);
+    insta::assert_snapshot!(stderr, @r###"
+    You have 0 conflicted commits.
+    Working copy now at: rlvkpnrz 7de3d6ff (empty) (no description set)
+    Parent commit      : qpvuntsm 2d5ad737 A
+    "###);
+    std::fs::write(repo_path.join("file"), "B\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=B"]);
+
+    let (_stdout, stderr) =
+        test_env.jj_cmd_ok(&repo_path, &["rebase", "-r=description(B)", "-d=root()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 1 commits onto destination
+    Rebased 1 descendant commits
+    New conflicts appeared in these commits:
+      rlvkpnrz d0d9a24c (conflict) B
+    To resolve the conflicts, start by updating to it:
+      jj new rlvkpnrzqnoo
+    Then use `jj resolve`, or edit the conflict markers in the file directly.
+    Once the conflicts are resolved, you may want inspect the result with `jj diff`.
+    Then run `jj squash` to move the resolution into the conflicted commit.
+    You have 1 conflicted commit.
+    Working copy now at: kkmpptxz 17d2dd2e (empty) (no description set)
+    Parent commit      : qpvuntsm 2d5ad737 A
+    Added 0 files, modified 1 files, removed 0 files
+    "###);
+
+    // Empty commits on top of a conflicted commit aren't counted
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["new", "description(B)"]);
+    insta::assert_snapshot!(stderr, @r###"
+    You have 1 conflicted commit.
+    Working copy now at: mzvwutvl 12428b2a (conflict) (empty) (no description set)
+    Parent commit      : rlvkpnrz d0d9a24c (conflict) B
+    Added 0 files, modified 1 files, removed 0 files
+    There are unresolved conflicts at these paths:
+    file    2-sided conflict including 1 deletion
+    "###);
+}
+
 #[test]
 fn test_report_conflicts_with_divergent_commits() {
     let test_env = TestEnvironment::default();
@@ -212,3 +308,59 @@ fn test_report_conflicts_with_divergent_commits() {
       zsuskuln hidden 8cc7fde6 (conflict) C3
     "###);
 }
+
+#[test]
+fn test_report_rebased_descendants_threshold() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    std::fs::write(repo_path.join("file"), "A\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=A"]);
+    std::fs::write(repo_path.join("file"), "B\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m=B"]);
+
+    // Rebasing fewer descendants than the threshold isn't reported
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "description(A)",
+            "-m=A2",
+            "--config-toml=ui.rebase-report-threshold=3",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: kkmpptxz cf597dbf (empty) (no description set)
+    Parent commit      : rlvkpnrz 8af7f145 B
+    "###);
+
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "describe",
+            "description(A2)",
+            "-m=A3",
+            "--config-toml=ui.rebase-report-threshold=2",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"");
+    insta::assert_snapshot!(stderr, @r###"
+    Rebased 2 descendant commits
+    Working copy now at: kkmpptxz 15f4ce48 (empty) (no description set)
+    Parent commit      : rlvkpnrz e4341404 B
+    "###);
+
+    // Same for descendants rebased onto the snapshotted working copy
+    test_env.jj_cmd_ok(&repo_path, &["edit", "description(A3)"]);
+    std::fs::write(repo_path.join("file"), "A3\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(
+        &repo_path,
+        &["status", "--config-toml=ui.rebase-report-threshold=3"],
+    );
+    insta::assert_snapshot!(stderr, @"");
+    std::fs::write(repo_path.join("file"), "A4\n").unwrap();
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&repo_path, &["status"]);
+    insta::assert_snapshot!(stderr, @"Rebased 1 descendant commits onto updated working copy");
+}
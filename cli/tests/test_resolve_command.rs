@@ -352,6 +352,36 @@ fn test_resolution() {
     // correctly.
 }
 
+#[test]
+fn test_resolve_timeout() {
+    let mut test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    create_commit(&test_env, &repo_path, "base", &[], &[("file", "base\n")]);
+    create_commit(&test_env, &repo_path, "a", &["base"], &[("file", "a\n")]);
+    create_commit(&test_env, &repo_path, "b", &["base"], &[("file", "b\n")]);
+    create_commit(&test_env, &repo_path, "conflict", &["a", "b"], &[]);
+
+    let editor_script = test_env.set_up_fake_editor();
+    std::fs::write(
+        &editor_script,
+        ["sleep 60", "write\nresolution\n"].join("\0"),
+    )
+    .unwrap();
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["resolve", "--config-toml=merge-tools.fake-editor.timeout=1"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Resolving conflicts in: file
+    Error: Failed to resolve conflicts
+    Caused by: Tool didn't exit within 1 seconds and was killed (run with --debug to see the exact invocation)
+    "###);
+    // The conflict is still there
+    insta::assert_snapshot!(test_env.jj_cmd_success(&repo_path, &["resolve", "--list"]), @"file    2-sided conflict");
+}
+
 fn check_resolve_produces_input_file(
     test_env: &mut TestEnvironment,
     repo_path: &Path,
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::TestEnvironment;
+use crate::common::{get_stderr_string, get_stdout_string, strip_last_line, TestEnvironment};
 
 #[test]
 fn test_syntax_error() {
@@ -455,6 +455,52 @@ fn test_alias_override() {
     "###);
 }
 
+#[test]
+fn test_alias_arg() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(
+        r###"
+    [revset-aliases]
+    'f(x)' = 'user'
+    "###,
+    );
+
+    // The one-off alias takes precedence over the config
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "-r", "f(_)", "--revset-alias", "f(a)=root()"],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    ◉  zzzzzzzz root() 00000000
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r", "my-root", "--revset-alias", "my-root"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid --revset-alias "my-root"
+    Hint: The alias must be specified as NAME=DEFINITION.
+    "###);
+
+    let stderr = test_env.jj_cmd_failure(
+        &repo_path,
+        &["log", "-r", "root()", "--revset-alias", "f(a, a)=root()"],
+    );
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to load --revset-alias "f(a, a)"
+    Caused by:  --> 1:3
+      |
+    1 | f(a, a)
+      |   ^--^
+      |
+      = Redefinition of function parameter
+    "###);
+}
+
 #[test]
 fn test_bad_alias_decl() {
     let test_env = TestEnvironment::default();
@@ -592,3 +638,165 @@ fn test_all_modifier() {
     For help, see https://github.com/martinvonz/jj/blob/main/docs/config.md.
     "###);
 }
+
+#[test]
+fn test_multiple_revisions_error_markers() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    std::fs::write(repo_path.join("file"), "a\n").unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["commit", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "b"]);
+    std::fs::write(repo_path.join("file"), "b\n").unwrap();
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(a)", "description(b)", "-m", "c"],
+    );
+
+    // The candidates are annotated with conflict and empty markers to help
+    // pick the right one.
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["new", "all()~root()"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "all()~root()" resolved to more than one revision
+    Hint: The revset "all()~root()" resolved to these revisions:
+      zsuskuln c6a31f23 (conflict) (empty) c
+      kkmpptxz 28610a3c b
+      qpvuntsm b5eb8ca5 a
+    Hint: Prefix the expression with 'all:' to allow any number of revisions (i.e. 'all:all()~root()').
+    "###);
+}
+
+#[test]
+fn test_id_prefix_shadowed_by_ref() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "a"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    let template = r#"commit_id.short() ++ " " ++ change_id.short() ++ " " ++ description"#;
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &["log", "--no-graph", "-r", "~root()", "-T", template],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    017c7f689ed7 kkmpptxzrspx b
+    d8d5f980a897 qpvuntsmwlqt a
+    "###);
+    let show_description =
+        |symbol: &str| test_env.jj_cmd_success(&repo_path, &["show", "-T", "description", symbol]);
+
+    // A bare id prefix resolves to the commit
+    insta::assert_snapshot!(show_description("d8d5"), @"a");
+
+    // A change id prefix resolves to the commit
+    insta::assert_snapshot!(show_description("qpvu"), @"a");
+
+    // An ambiguous commit id prefix is reported by the revset engine
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["show", "0"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Commit ID prefix "0" is ambiguous
+    "###);
+
+    // A branch of the same name takes precedence over the id prefix
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "-r", "@", "d8d5"]);
+    insta::assert_snapshot!(show_description("d8d5"), @"b");
+
+    // So does a tag
+    let git_repo = git2::Repository::open(repo_path.join(".jj/repo/store/git")).unwrap();
+    let commit_id = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r",
+            "description(a)",
+            "-T",
+            "commit_id",
+        ],
+    );
+    let object = git_repo.revparse_single(&commit_id).unwrap();
+    git_repo.tag_lightweight("017c", &object, false).unwrap();
+    test_env.jj_cmd_ok(&repo_path, &["git", "import"]);
+    insta::assert_snapshot!(show_description("017c"), @"a");
+
+    // So does a revset alias
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "show",
+            "-T",
+            "description",
+            "--config-toml=revset-aliases.qpvu = '@'",
+            "qpvu",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"b");
+
+    // Git refs are resolved by full name, such as "refs/tags/017c", which
+    // never looks like an id prefix
+    insta::assert_snapshot!(show_description("refs/tags/017c"), @"a");
+
+    // A divergent change id is reported by the revset engine. The tagged
+    // commit is immutable.
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["--ignore-immutable", "describe", "-r", "qpvu", "-m", "a1"],
+    );
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &[
+            "--at-op=@-",
+            "--ignore-immutable",
+            "describe",
+            "-r",
+            "qpvu",
+            "-m",
+            "a2",
+        ],
+    );
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["show", "qpvu"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Concurrent modification detected, resolving automatically.
+    Error: Revset "qpvu" resolved to more than one revision
+    Hint: The revset "qpvu" resolved to these revisions:
+      qpvuntsm?? 76015ad3 (empty) a2
+      qpvuntsm?? df47578e (empty) a1
+    Hint: Some of these commits have the same change id. Abandon one of them with `jj abandon -r <REVISION>`.
+    "###);
+}
+
+#[test]
+fn test_resolve_single_commit_id() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["new", "-m", "b"]);
+    let resolve_commit_id = |revision: &str| {
+        test_env.cargo_bin_cmd(
+            "fake-embedder",
+            &repo_path,
+            &["resolve-single-commit-id", revision],
+        )
+    };
+
+    let assert = resolve_commit_id("@").assert().success();
+    let commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tcommit_id"]);
+    assert_eq!(get_stdout_string(&assert), format!("{commit_id}\n"));
+
+    // Errors are reported in the same way as resolve_single_rev(), which
+    // `jj show` uses
+    for revision in ["none()", "@ | @-"] {
+        let assert = resolve_commit_id(revision).assert().code(1);
+        let stderr = get_stderr_string(&assert);
+        let expected = test_env.jj_cmd_failure(&repo_path, &["show", revision]);
+        assert_eq!(strip_last_line(&stderr), expected);
+    }
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["show", "@ | @-"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Revset "@ | @-" resolved to more than one revision
+    Hint: The revset "@ | @-" resolved to these revisions:
+      rlvkpnrz 99af05e4 (empty) b
+      qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+}
@@ -35,7 +35,7 @@ fn test_root(backend: TestRepoBackend) {
 #[test]
 fn test_root_outside_a_repo() {
     let test_env = TestEnvironment::default();
-    let stdout = test_env.jj_cmd_failure(Path::new("/"), &["root"]);
+    let stdout = test_env.jj_cmd_no_workspace(Path::new("/"), &["root"]);
     insta::assert_snapshot!(stdout, @r###"
     Error: There is no jj repo in "."
     "###);
@@ -0,0 +1,103 @@
+// Copyright 2024 The Jujutsu Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// https://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use crate::common::{get_stderr_string, strip_last_line, TestEnvironment};
+
+/// Runs `fake-embedder` and returns the operation id it reported.
+fn run_and_report_op_id(test_env: &TestEnvironment, current_dir: &Path, args: &[&str]) -> String {
+    let assert = test_env
+        .cargo_bin_cmd("fake-embedder", current_dir, args)
+        .assert()
+        .success();
+    let stderr = get_stderr_string(&assert);
+    let last_line = stderr.lines().last().unwrap();
+    last_line
+        .strip_prefix("operation_id: ")
+        .unwrap_or_else(|| panic!("unexpected stderr: {stderr}"))
+        .to_owned()
+}
+
+#[test]
+fn test_run_and_report_git_init() {
+    let test_env = TestEnvironment::default();
+    let op_id = run_and_report_op_id(&test_env, test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    assert_eq!(op_id, test_env.current_operation_id(&repo_path));
+}
+
+#[test]
+fn test_run_and_report_mutating_command() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let old_op_id = test_env.current_operation_id(&repo_path);
+
+    let op_id = run_and_report_op_id(&test_env, &repo_path, &["describe", "-m", "description"]);
+    assert_ne!(op_id, old_op_id);
+    assert_eq!(op_id, test_env.current_operation_id(&repo_path));
+}
+
+#[test]
+fn test_run_and_report_read_only_command() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let old_op_id = test_env.current_operation_id(&repo_path);
+
+    let op_id = run_and_report_op_id(&test_env, &repo_path, &["log"]);
+    assert_eq!(op_id, "none");
+    assert_eq!(test_env.current_operation_id(&repo_path), old_op_id);
+
+    // Snapshotting the working copy commits an operation
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    let op_id = run_and_report_op_id(&test_env, &repo_path, &["log"]);
+    assert_ne!(op_id, old_op_id);
+    assert_eq!(op_id, test_env.current_operation_id(&repo_path));
+}
+
+#[test]
+fn test_run_and_report_status_sink() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let status_path = test_env.env_root().join("status");
+    let run_with_status_sink = |args: &[&str]| {
+        let mut cmd = test_env.cargo_bin_cmd("fake-embedder", &repo_path, args);
+        cmd.env("FAKE_EMBEDDER_STATUS_FILE", &status_path);
+        cmd.assert()
+    };
+
+    // Status messages go to the sink
+    let assert = run_with_status_sink(&["new"]).success();
+    let stderr = get_stderr_string(&assert);
+    assert!(stderr.starts_with("operation_id: "), "{stderr}");
+    let status = std::fs::read_to_string(&status_path).unwrap();
+    insta::assert_snapshot!(status, @r###"
+    Working copy now at: rlvkpnrz 65b6b74e (empty) (no description set)
+    Parent commit      : qpvuntsm 230dd059 (empty) (no description set)
+    "###);
+
+    // Errors and their hints stay on stderr
+    test_env.jj_cmd_ok(&repo_path, &["branch", "create", "main"]);
+    let assert = run_with_status_sink(&["new", "mian"]).failure();
+    let stderr = get_stderr_string(&assert);
+    insta::assert_snapshot!(strip_last_line(&stderr), @r###"
+    Error: Revision "mian" doesn't exist
+    Hint: Did you mean "main"?
+    "###);
+    let status = std::fs::read_to_string(&status_path).unwrap();
+    insta::assert_snapshot!(status, @"");
+}
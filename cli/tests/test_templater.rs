@@ -342,6 +342,53 @@ fn test_templater_alias_override() {
     insta::assert_snapshot!(stdout, @"arg");
 }
 
+#[test]
+fn test_templater_alias_arg() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    test_env.add_config(
+        r#"
+    [template-aliases]
+    'f(x)' = '"user"'
+    "#,
+    );
+
+    // The one-off alias takes precedence over the config
+    let stdout = test_env.jj_cmd_success(
+        &repo_path,
+        &[
+            "log",
+            "--no-graph",
+            "-r@",
+            "-T",
+            r#"f(_)"#,
+            "--template-alias",
+            r#"f(a)="arg""#,
+        ],
+    );
+    insta::assert_snapshot!(stdout, @"arg");
+
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["log", "-r@", "--template-alias", "f(a)"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Invalid --template-alias "f(a)"
+    Hint: The alias must be specified as NAME=DEFINITION.
+    "###);
+
+    let stderr =
+        test_env.jj_cmd_failure(&repo_path, &["log", "-r@", "--template-alias", "f(a, a)=a"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Failed to load --template-alias "f(a, a)"
+    Caused by:  --> 1:3
+      |
+    1 | f(a, a)
+      |   ^--^
+      |
+      = Redefinition of function parameter
+    "###);
+}
+
 #[test]
 fn test_templater_bad_alias_decl() {
     let test_env = TestEnvironment::default();
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::TestEnvironment;
+use std::path::Path;
+
+use crate::common::{get_stderr_string, strip_last_line, TestEnvironment};
 
 #[test]
 fn test_snapshot_large_file() {
@@ -52,3 +54,130 @@ fn test_snapshot_large_file() {
         This will increase the maximum file size allowed for new files, for this command only.
     "###);
 }
+
+#[cfg(unix)]
+#[test]
+fn test_working_copy_lock_timeout() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    let jj_path = assert_cmd::cargo::cargo_bin("jj");
+    // Runs the given shell script with `$0` set to the `jj` binary after the
+    // repo is loaded, then starts a working-copy mutation.
+    let mutate_working_copy = |test_env: &TestEnvironment, script: &str| {
+        std::fs::write(repo_path.join("file"), "contents").unwrap();
+        test_env
+            .cargo_bin_cmd(
+                "fake-embedder",
+                &repo_path,
+                &[
+                    "mutate-working-copy",
+                    "--",
+                    "sh",
+                    "-c",
+                    script,
+                    jj_path.to_str().unwrap(),
+                ],
+            )
+            .assert()
+    };
+
+    // Another process updates the working copy after the repo is loaded
+    let assert = mutate_working_copy(&test_env, r#""$0" new 'root()'"#).failure();
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(strip_last_line(&stderr), @r###"
+    Working copy now at: rlvkpnrz 6acbecf3 (empty) (no description set)
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 1 files
+    Error: Concurrent working copy operation. Try again.
+    "###);
+
+    // The working copy doesn't settle down within the timeout
+    test_env.add_config(r#"working-copy.lock-timeout = 100"#);
+    let assert = mutate_working_copy(&test_env, r#""$0" new 'root()'"#).failure();
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    insta::assert_snapshot!(strip_last_line(&stderr), @r###"
+    Working copy now at: kkmpptxz fcdbbd73 (empty) (no description set)
+    Parent commit      : zzzzzzzz 00000000 (empty) (no description set)
+    Added 0 files, modified 0 files, removed 1 files
+    Error: Concurrent working copy operation. Try again.
+    "###);
+
+    // Another process restores the working copy while the command waits
+    test_env.add_config(r#"working-copy.lock-timeout = 10000"#);
+    let assert = mutate_working_copy(
+        &test_env,
+        r#""$0" new 'root()' && ("$0" undo >/dev/null 2>&1 &)"#,
+    )
+    .success();
+    let stderr = test_env.normalize_output(&get_stderr_string(&assert));
+    let wc_commit_id =
+        test_env.jj_cmd_success(&repo_path, &["log", "--no-graph", "-r@", "-Tcommit_id"]);
+    assert_eq!(
+        strip_last_line(&stderr).lines().last().unwrap(),
+        format!("Working copy commit: {wc_commit_id}")
+    );
+
+    test_env.add_config(r#"working-copy.lock-timeout = "soon""#);
+    let stderr = test_env.jj_cmd_failure(&repo_path, &["sparse", "reset"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Config error: Invalid `working-copy.lock-timeout`
+    Caused by: invalid type: string "soon", expected an integer for key `working-copy.lock-timeout` in ../config/config0004.toml
+    Hint: Check the `working-copy.lock-timeout` value; expected a number of milliseconds.
+    "###);
+}
+
+/// Runs `require_clean_working_copy()` with the given `working-copy.clean`
+/// setting, and returns its stderr.
+fn require_clean(test_env: &TestEnvironment, repo_path: &Path, clean: &str) -> String {
+    let config = format!("--config-toml=working-copy.clean='{clean}'");
+    let assert = test_env
+        .cargo_bin_cmd("fake-embedder", repo_path, &["require-clean", &config])
+        .assert();
+    // Drop the operation id printed by fake-embedder
+    let stderr = get_stderr_string(&assert);
+    test_env.normalize_output(strip_last_line(&stderr))
+}
+
+#[test]
+fn test_require_clean_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+
+    // An empty commit without description is clean either way
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-diff"), @r###"
+    The working copy is clean
+    "###);
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-commit"), @r###"
+    The working copy is clean
+    "###);
+
+    // A description only matters for "empty-commit"
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "description"]);
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-diff"), @r###"
+    The working copy is clean
+    "###);
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-commit"), @r###"
+    Error: The working copy a5e65f43a68b is not clean
+    Hint: Run `jj new` to start a new change on top of it first.
+    "###);
+
+    // Changes in the working copy are snapshotted first
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", ""]);
+    std::fs::write(repo_path.join("file"), "contents").unwrap();
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-diff"), @r###"
+    Error: The working copy e41e21eea7b4 is not clean
+    Hint: Run `jj new` to start a new change on top of it first.
+    "###);
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "empty-commit"), @r###"
+    Error: The working copy e41e21eea7b4 is not clean
+    Hint: Run `jj new` to start a new change on top of it first.
+    "###);
+
+    insta::assert_snapshot!(require_clean(&test_env, &repo_path, "bad"), @r###"
+    Config error: Invalid `working-copy.clean`
+    Caused by: enum CleanWorkingCopy does not have variant constructor bad
+    Hint: Check the `working-copy.clean` value; expected one of `empty-diff` or `empty-commit`.
+    "###);
+}
@@ -14,7 +14,7 @@
 
 use std::path::Path;
 
-use crate::common::TestEnvironment;
+use crate::common::{get_stdout_string, TestEnvironment};
 
 /// Test adding a second workspace
 #[test]
@@ -406,6 +406,86 @@ fn test_workspaces_updated_by_other() {
     "###);
 }
 
+#[test]
+fn test_workspaces_stale_custom_messages() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+    test_env.add_config(
+        r#"
+        [messages]
+        stale-working-copy = "Stale since {operation}"
+        stale-working-copy-hint = "See the internal wiki"
+        checkout-stats = "+{added} ~{modified} -{removed}"
+        "#,
+    );
+
+    std::fs::write(main_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["new"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+
+    // Rewrite the check-out commit in one workspace.
+    std::fs::write(main_path.join("file"), "changed in main\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["squash"]);
+
+    let stderr = test_env.jj_cmd_failure(&secondary_path, &["st"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: Stale since f46ea702e886
+    Hint: See the internal wiki
+    "###);
+    let (_stdout, stderr) = test_env.jj_cmd_ok(&secondary_path, &["workspace", "update-stale"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Working copy now at: pmmvwywv f42c7be0 (empty) (no description set)
+    +0 ~1 -0
+    "###);
+}
+
+#[test]
+fn test_workspaces_allow_stale_working_copy() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "main"]);
+    let main_path = test_env.env_root().join("main");
+    let secondary_path = test_env.env_root().join("secondary");
+
+    std::fs::write(main_path.join("file"), "contents\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["new"]);
+    test_env.jj_cmd_ok(&main_path, &["workspace", "add", "../secondary"]);
+
+    // Rewrite the check-out commit in one workspace.
+    std::fs::write(main_path.join("file"), "changed in main\n").unwrap();
+    test_env.jj_cmd_ok(&main_path, &["squash"]);
+
+    // Read-only commands see the repo at the working copy's operation
+    let (stdout, stderr) = test_env.jj_cmd_ok(
+        &secondary_path,
+        &[
+            "log",
+            "-T",
+            "commit_id.short()",
+            "--allow-stale-working-copy",
+        ],
+    );
+    insta::assert_snapshot!(stdout, @r###"
+    @  3224de8ae048
+    │ ◉  06b57f44a3ca
+    ├─╯
+    ◉  506f4ec3c2c6
+    ◉  000000000000
+    "###);
+    insta::assert_snapshot!(stderr, @r###"
+    Warning: The working copy is stale (not updated since operation f46ea702e886). Loading the repo at that operation.
+    "###);
+
+    // The working copy is still stale
+    let stderr = test_env.jj_cmd_failure(&secondary_path, &["st"]);
+    insta::assert_snapshot!(stderr, @r###"
+    Error: The working copy is stale (not updated since operation f46ea702e886).
+    Hint: Run `jj workspace update-stale` to update it.
+    See https://github.com/martinvonz/jj/blob/main/docs/working-copy.md#stale-working-copy for more information.
+    "###);
+}
+
 #[test]
 fn test_workspaces_current_op_discarded_by_other() {
     let test_env = TestEnvironment::default();
@@ -828,3 +908,33 @@ fn get_log_output(test_env: &TestEnvironment, cwd: &Path) -> String {
     "#;
     test_env.jj_cmd_success(cwd, &["log", "-T", template, "-r", "all()"])
 }
+
+#[test]
+fn test_workspaces_wc_parent_commits() {
+    let test_env = TestEnvironment::default();
+    test_env.jj_cmd_ok(test_env.env_root(), &["git", "init", "repo"]);
+    let repo_path = test_env.env_root().join("repo");
+    test_env.jj_cmd_ok(&repo_path, &["describe", "-m", "left"]);
+    test_env.jj_cmd_ok(&repo_path, &["new", "root()", "-m", "right"]);
+    test_env.jj_cmd_ok(
+        &repo_path,
+        &["new", "description(left)", "description(right)"],
+    );
+    let wc_parents = || {
+        let assert = test_env
+            .cargo_bin_cmd("fake-embedder", &repo_path, &["wc-parents"])
+            .assert()
+            .success();
+        get_stdout_string(&assert)
+    };
+
+    insta::assert_snapshot!(wc_parents(), @r###"
+    2 parents
+    left
+    right
+    "###);
+
+    // Nothing is checked out once the workspace is forgotten
+    test_env.jj_cmd_ok(&repo_path, &["workspace", "forget"]);
+    insta::assert_snapshot!(wc_parents(), @"0 parents");
+}
@@ -82,7 +82,9 @@ fn parse_git_ref(ref_name: &str) -> Option<RefName> {
     }
 }
 
-fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
+/// Returns the full Git ref name of the `parsed_ref`, or `None` if it can't be
+/// represented in Git.
+pub fn to_git_ref_name(parsed_ref: &RefName) -> Option<String> {
     match parsed_ref {
         RefName::LocalBranch(branch) => {
             (!branch.is_empty() && branch != "HEAD").then(|| format!("refs/heads/{branch}"))
@@ -14,7 +14,7 @@
 
 #![allow(missing_docs)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{fs, io, iter};
 
@@ -36,6 +36,17 @@ pub enum GitIgnoreError {
     Underlying(#[from] ignore::Error),
 }
 
+/// The `.gitignore` line which decided whether a path is ignored.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitIgnoreMatch {
+    /// Whether the path is ignored, i.e. the pattern isn't negated by `!`.
+    pub ignored: bool,
+    /// The pattern as written in the ignore file.
+    pub pattern: String,
+    /// The ignore file the pattern was read from, if known.
+    pub source: Option<PathBuf>,
+}
+
 /// Models the effective contents of multiple .gitignore files.
 #[derive(Debug)]
 pub struct GitIgnoreFile {
@@ -59,6 +70,15 @@ impl GitIgnoreFile {
         self: &Arc<GitIgnoreFile>,
         prefix: &str,
         input: &[u8],
+    ) -> Result<Arc<GitIgnoreFile>, GitIgnoreError> {
+        self.chain_with_source(prefix, input, None)
+    }
+
+    fn chain_with_source(
+        self: &Arc<GitIgnoreFile>,
+        prefix: &str,
+        input: &[u8],
+        source: Option<&Path>,
     ) -> Result<Arc<GitIgnoreFile>, GitIgnoreError> {
         let mut builder = gitignore::GitignoreBuilder::new(prefix);
         for (i, input_line) in input.split(|b| *b == b'\n').enumerate() {
@@ -69,9 +89,9 @@ impl GitIgnoreFile {
                     line: String::from_utf8_lossy(input_line).to_string(),
                     source: err,
                 })?;
-            // FIXME: do we need to provide the `from` argument? Is it for providing
-            // diagnostics or correctness?
-            builder.add_line(None, line)?;
+            // The `from` argument is only used to report where a pattern came
+            // from.
+            builder.add_line(source.map(Path::to_path_buf), line)?;
         }
         let matcher = builder.build()?;
         let parent = if self.matcher.is_empty() {
@@ -96,7 +116,7 @@ impl GitIgnoreFile {
                 path: file.clone(),
                 source: err,
             })?;
-            self.chain(prefix, &buf)
+            self.chain_with_source(prefix, &buf, Some(&file))
         } else {
             Ok(self.clone())
         }
@@ -132,6 +152,31 @@ impl GitIgnoreFile {
         };
         self.matches_helper(path, is_dir)
     }
+
+    /// Returns the last applicable `.gitignore` line for the specified path,
+    /// or `None` if no pattern applies. Like `matches()`, a path ending with
+    /// slash is considered a directory.
+    ///
+    /// This is slower than `matches()`, and is meant for explaining why a
+    /// path is ignored.
+    pub fn matched(&self, path: &str) -> Option<GitIgnoreMatch> {
+        let (path, is_dir) = match path.strip_suffix('/') {
+            Some(path) => (path, true),
+            None => (path, false),
+        };
+        iter::successors(Some(self), |file| file.parent.as_deref()).find_map(|file| {
+            let (glob, ignored) = match file.matcher.matched_path_or_any_parents(path, is_dir) {
+                ignore::Match::None => return None,
+                ignore::Match::Ignore(glob) => (glob, true),
+                ignore::Match::Whitelist(glob) => (glob, false),
+            };
+            Some(GitIgnoreMatch {
+                ignored,
+                pattern: glob.original().to_owned(),
+                source: glob.from().map(Path::to_path_buf),
+            })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +277,43 @@ mod tests {
         assert!(file.matches("dir/subdir/foo"));
     }
 
+    #[test]
+    fn test_gitignore_matched() {
+        let temp_dir = testutils::new_temp_dir();
+        let root_file = temp_dir.path().join("ignore");
+        fs::write(&root_file, "*.o\n/build/\n").unwrap();
+        let file = GitIgnoreFile::empty()
+            .chain_with_file("", root_file.clone())
+            .unwrap()
+            .chain("dir/", b"!keep.o\n")
+            .unwrap();
+        assert_eq!(file.matched("foo"), None);
+        assert_eq!(
+            file.matched("dir/foo.o"),
+            Some(GitIgnoreMatch {
+                ignored: true,
+                pattern: "*.o".to_owned(),
+                source: Some(root_file.clone()),
+            })
+        );
+        assert_eq!(
+            file.matched("dir/keep.o"),
+            Some(GitIgnoreMatch {
+                ignored: false,
+                pattern: "!keep.o".to_owned(),
+                source: None,
+            })
+        );
+        assert_eq!(
+            file.matched("build/sub/foo"),
+            Some(GitIgnoreMatch {
+                ignored: true,
+                pattern: "/build/".to_owned(),
+                source: Some(root_file),
+            })
+        );
+    }
+
     #[test]
     fn test_gitignore_unusual_symbols() {
         assert!(matches(b"\\*\n", "*"));
@@ -51,6 +51,13 @@ pub fn to_reverse_hex(forward_hex: &str) -> Option<String> {
         .collect()
 }
 
+/// Encodes the given bytes as reverse hex digits (`z` to `k`).
+pub fn encode_reverse_hex(data: &[u8]) -> String {
+    iter_half_bytes(data)
+        .map(|v| char::from(b'z' - v))
+        .collect()
+}
+
 /// Calculates common prefix length of two bytes. The length to be returned is
 /// a number of hexadecimal digits.
 pub fn common_hex_len(bytes_a: &[u8], bytes_b: &[u8]) -> usize {
@@ -99,4 +106,17 @@ mod tests {
         assert_eq!(to_reverse_hex("g"), None);
         assert_eq!(to_forward_hex("j"), None);
     }
+
+    #[test]
+    fn test_encode_reverse_hex() {
+        assert_eq!(encode_reverse_hex(b""), "");
+        assert_eq!(
+            encode_reverse_hex(b"\x01\x23\x45\x67\x89\xab\xcd\xef"),
+            "zyxwvutsrqponmlk"
+        );
+        assert_eq!(
+            to_forward_hex(&encode_reverse_hex(b"\xf0\x0d")),
+            Some("f00d".to_string())
+        );
+    }
 }
@@ -764,6 +764,7 @@ impl TreeState {
             fsmonitor_settings,
             progress,
             max_new_file_size,
+            parallelism,
         } = options;
 
         let sparse_matcher = self.sparse_matcher();
@@ -790,6 +791,19 @@ impl TreeState {
         let (file_states_tx, file_states_rx) = channel();
         let (present_files_tx, present_files_rx) = channel();
 
+        // Use a dedicated thread pool if the parallelism is specified, and the
+        // global one otherwise.
+        let thread_pool = parallelism
+            .map(|num_threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()
+                    .map_err(|err| SnapshotError::Other {
+                        message: "Failed to start snapshot threads".to_string(),
+                        err: err.into(),
+                    })
+            })
+            .transpose()?;
         trace_span!("traverse filesystem").in_scope(|| -> Result<(), SnapshotError> {
             let current_tree = self.current_tree()?;
             let directory_to_visit = DirectoryToVisit {
@@ -798,16 +812,22 @@ impl TreeState {
                 git_ignore: base_ignores,
                 file_states: self.file_states.all(),
             };
-            self.visit_directory(
-                &matcher,
-                &current_tree,
-                tree_entries_tx,
-                file_states_tx,
-                present_files_tx,
-                directory_to_visit,
-                progress,
-                max_new_file_size,
-            )
+            let visit = || {
+                self.visit_directory(
+                    &matcher,
+                    &current_tree,
+                    tree_entries_tx,
+                    file_states_tx,
+                    present_files_tx,
+                    directory_to_visit,
+                    progress,
+                    max_new_file_size,
+                )
+            };
+            match &thread_pool {
+                Some(thread_pool) => thread_pool.install(visit),
+                None => visit(),
+            }
         })?;
 
         let mut tree_builder = MergedTreeBuilder::new(self.tree_id.clone());
@@ -1331,6 +1351,7 @@ impl TreeState {
             added_files: added_stats.added_files,
             removed_files: removed_stats.removed_files,
             skipped_files: added_stats.skipped_files,
+            skipped_paths: added_stats.skipped_paths,
         })
     }
 
@@ -1347,6 +1368,7 @@ impl TreeState {
             added_files: 0,
             removed_files: 0,
             skipped_files: 0,
+            skipped_paths: vec![],
         };
         let mut changed_file_states = Vec::new();
         let mut deleted_files = HashSet::new();
@@ -1378,15 +1400,17 @@ impl TreeState {
             if present_before {
                 fs::remove_file(&disk_path).ok();
             } else if disk_path.exists() {
-                changed_file_states.push((path, FileState::placeholder()));
                 stats.skipped_files += 1;
+                stats.skipped_paths.push(path.clone());
+                changed_file_states.push((path, FileState::placeholder()));
                 continue;
             }
             if after.is_present() {
                 let skip = create_parent_dirs(&self.working_copy_path, &path)?;
                 if skip {
-                    changed_file_states.push((path, FileState::placeholder()));
                     stats.skipped_files += 1;
+                    stats.skipped_paths.push(path.clone());
+                    changed_file_states.push((path, FileState::placeholder()));
                     continue;
                 }
             }
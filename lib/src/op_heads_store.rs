@@ -129,7 +129,13 @@ where
         return Ok(op_head.clone());
     }
 
-    op_heads.sort_by_key(|op| op.metadata().end_time.timestamp);
+    // Sort by id as well so the merge order doesn't depend on the order in
+    // which the op heads were listed.
+    op_heads.sort_by(|op1, op2| {
+        let key1 = (op1.metadata().end_time.timestamp, op1.id());
+        let key2 = (op2.metadata().end_time.timestamp, op2.id());
+        key1.cmp(&key2)
+    });
     let new_op = resolver(op_heads)?;
     let mut old_op_heads = ancestor_op_heads;
     old_op_heads.extend_from_slice(new_op.parent_ids());
@@ -19,9 +19,11 @@ use std::collections::{HashMap, HashSet};
 use std::slice;
 use std::sync::Arc;
 
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone as _};
 use itertools::Itertools as _;
 use thiserror::Error;
 
+use crate::backend::MillisSinceEpoch;
 use crate::object_id::{HexPrefix, PrefixResolution};
 use crate::op_heads_store::{OpHeadResolutionError, OpHeadsStore};
 use crate::op_store::{OpStore, OpStoreError, OpStoreResult, OperationId};
@@ -62,8 +64,19 @@ pub enum OpsetResolutionError {
     #[error(r#"No operation ID matching "{0}""#)]
     NoSuchOperation(String),
     /// Operation ID prefix matches multiple operations.
-    #[error(r#"Operation ID prefix "{0}" is ambiguous"#)]
-    AmbiguousIdPrefix(String),
+    #[error(r#"Operation ID prefix "{prefix}" is ambiguous"#)]
+    AmbiguousIdPrefix {
+        /// The ambiguous prefix.
+        prefix: String,
+        /// Operations reachable from the current heads which match the prefix.
+        candidates: Vec<OperationId>,
+    },
+    /// Invalid time expression.
+    #[error(r#"Invalid time expression "{0}""#)]
+    InvalidTimeExpression(String),
+    /// No operation was completed at or before the given time.
+    #[error(r#"No operation found at or before "{0}""#)]
+    NoOperationAtTime(String),
 }
 
 /// Resolves operation set expression without loading a repo.
@@ -105,18 +118,27 @@ pub fn resolve_op_at(
 
 /// Resolves operation set expression with the given "@" symbol resolution
 /// callbacks.
+///
+/// Besides operation IDs, the symbol may be `root`, which resolves to the
+/// initial operation, or a time expression such as `@{yesterday}` or
+/// `2 hours ago`, which resolves to the latest operation completed at or before
+/// that time.
 fn resolve_single_op(
     op_store: &Arc<dyn OpStore>,
     get_current_op: impl FnOnce() -> Result<Operation, OpsetEvaluationError>,
-    get_head_ops: impl FnOnce() -> OpStoreResult<Vec<Operation>>,
+    get_head_ops: impl Fn() -> OpStoreResult<Vec<Operation>>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
     let op_symbol = op_str.trim_end_matches(['-', '+']);
     let op_postfix = &op_str[op_symbol.len()..];
-    let head_ops = op_postfix.contains('+').then(get_head_ops).transpose()?;
+    let head_ops = op_postfix.contains('+').then(&get_head_ops).transpose()?;
     let mut operation = match op_symbol {
         "@" => get_current_op(),
-        s => resolve_single_op_from_store(op_store, s),
+        "root" => resolve_root_op(&get_head_ops()?),
+        s => match parse_time_symbol(s, Local::now())? {
+            Some(time) => resolve_op_at_time(&get_head_ops()?, time, s),
+            None => resolve_single_op_from_store(op_store, &get_head_ops, s),
+        },
     }?;
     for c in op_postfix.chars() {
         let mut neighbor_ops = match c {
@@ -133,8 +155,99 @@ fn resolve_single_op(
     Ok(operation)
 }
 
+/// Parses `@{<time>}`, or a bare time expression that can't be an operation
+/// ID.
+fn parse_time_symbol(
+    symbol: &str,
+    now: DateTime<Local>,
+) -> Result<Option<MillisSinceEpoch>, OpsetResolutionError> {
+    if let Some(text) = symbol.strip_prefix("@{").and_then(|s| s.strip_suffix('}')) {
+        let time = parse_time_expression(text, now)
+            .ok_or_else(|| OpsetResolutionError::InvalidTimeExpression(text.to_owned()))?;
+        Ok(Some(time))
+    } else if HexPrefix::new(symbol).is_some() {
+        Ok(None)
+    } else {
+        Ok(parse_time_expression(symbol, now))
+    }
+}
+
+/// Parses human time expression such as `now`, `yesterday`, `2 hours ago`,
+/// `2024-01-31 12:00`, or RFC 3339 timestamp.
+fn parse_time_expression(text: &str, now: DateTime<Local>) -> Option<MillisSinceEpoch> {
+    let text = text.trim();
+    let time = match text {
+        "now" => now.fixed_offset(),
+        "yesterday" => now
+            .checked_sub_signed(TimeDelta::try_days(1)?)?
+            .fixed_offset(),
+        _ => {
+            if let Some(rest) = text.strip_suffix(" ago") {
+                let (count, unit) = rest.trim().split_once(' ')?;
+                let count: i64 = count.parse().ok()?;
+                let unit = unit.trim();
+                let delta = match unit.strip_suffix('s').unwrap_or(unit) {
+                    "second" => TimeDelta::try_seconds(count)?,
+                    "minute" => TimeDelta::try_minutes(count)?,
+                    "hour" => TimeDelta::try_hours(count)?,
+                    "day" => TimeDelta::try_days(count)?,
+                    "week" => TimeDelta::try_weeks(count)?,
+                    _ => return None,
+                };
+                now.checked_sub_signed(delta)?.fixed_offset()
+            } else if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+                time
+            } else {
+                let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+                    .iter()
+                    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                    .or_else(|| {
+                        let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+                        date.and_hms_opt(0, 0, 0)
+                    })?;
+                Local.from_local_datetime(&naive).earliest()?.fixed_offset()
+            }
+        }
+    };
+    Some(MillisSinceEpoch(time.timestamp_millis()))
+}
+
+/// Finds the operation without parents.
+fn resolve_root_op(head_ops: &[Operation]) -> Result<Operation, OpsetEvaluationError> {
+    let mut root_ops: Vec<Operation> = walk_ancestors(head_ops)
+        .filter_ok(|op| op.parent_ids().is_empty())
+        .try_collect()?;
+    match root_ops.len() {
+        0 => Err(OpsetResolutionError::EmptyOperations("root".to_owned()).into()),
+        1 => Ok(root_ops.pop().unwrap()),
+        _ => Err(OpsetResolutionError::MultipleOperations("root".to_owned()).into()),
+    }
+}
+
+/// Finds the latest operation that was completed at or before the given time.
+///
+/// The root operation isn't considered since it doesn't have a meaningful
+/// timestamp.
+fn resolve_op_at_time(
+    head_ops: &[Operation],
+    time: MillisSinceEpoch,
+    symbol: &str,
+) -> Result<Operation, OpsetEvaluationError> {
+    for op in walk_ancestors(head_ops) {
+        let op = op?;
+        if op.parent_ids().is_empty() {
+            continue;
+        }
+        if op.metadata().end_time.timestamp <= time {
+            return Ok(op);
+        }
+    }
+    Err(OpsetResolutionError::NoOperationAtTime(symbol.to_owned()).into())
+}
+
 fn resolve_single_op_from_store(
     op_store: &Arc<dyn OpStore>,
+    get_head_ops: impl Fn() -> OpStoreResult<Vec<Operation>>,
     op_str: &str,
 ) -> Result<Operation, OpsetEvaluationError> {
     if op_str.is_empty() {
@@ -151,7 +264,15 @@ fn resolve_single_op_from_store(
             Ok(Operation::new(op_store.clone(), op_id, data))
         }
         PrefixResolution::AmbiguousMatch => {
-            Err(OpsetResolutionError::AmbiguousIdPrefix(op_str.to_owned()).into())
+            let candidates = walk_ancestors(&get_head_ops()?)
+                .map_ok(|op| op.id().clone())
+                .filter_ok(|id| prefix.matches(id))
+                .try_collect()?;
+            Err(OpsetResolutionError::AmbiguousIdPrefix {
+                prefix: op_str.to_owned(),
+                candidates,
+            }
+            .into())
         }
     }
 }
@@ -1285,6 +1285,22 @@ fn fold_generation(expression: &Rc<RevsetExpression>) -> TransformedExpression {
     })
 }
 
+/// Replaces references to the working-copy commit of `workspace_id` (such as
+/// `@`) with the given commit. Returns new tree.
+pub fn substitute_working_copy(
+    expression: Rc<RevsetExpression>,
+    workspace_id: &WorkspaceId,
+    commit_id: &CommitId,
+) -> Rc<RevsetExpression> {
+    transform_expression_bottom_up(&expression, |expression| match expression.as_ref() {
+        RevsetExpression::CommitRef(RevsetCommitRef::WorkingCopy(id)) if id == workspace_id => {
+            Some(RevsetExpression::commit(commit_id.clone()))
+        }
+        _ => None,
+    })
+    .unwrap_or(expression)
+}
+
 /// Rewrites the given `expression` tree to reduce evaluation cost. Returns new
 /// tree.
 pub fn optimize(expression: Rc<RevsetExpression>) -> Rc<RevsetExpression> {
@@ -2659,6 +2675,36 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_substitute_working_copy() {
+        let settings = insta_settings();
+        let _guard = settings.bind_to_scope();
+        let main_workspace_id = WorkspaceId::new("main".to_string());
+        let commit_id = CommitId::from_hex("0123");
+
+        let expression = parse_with_workspace("::@- | other@", &main_workspace_id).unwrap();
+        insta::assert_debug_snapshot!(
+            substitute_working_copy(expression, &main_workspace_id, &commit_id), @r###"
+        Union(
+            Ancestors {
+                heads: Ancestors {
+                    heads: Commits(
+                        [
+                            CommitId("0123"),
+                        ],
+                    ),
+                    generation: 1..2,
+                },
+                generation: 0..18446744073709551615,
+            },
+            CommitRef(WorkingCopy(WorkspaceId("other"))),
+        )
+        "###);
+        let expression = parse_with_workspace("other@", &main_workspace_id).unwrap();
+        insta::assert_debug_snapshot!(
+            substitute_working_copy(expression, &main_workspace_id, &commit_id), @r###"CommitRef(WorkingCopy(WorkspaceId("other")))"###);
+    }
+
     #[test]
     fn test_optimize_subtree() {
         let settings = insta_settings();
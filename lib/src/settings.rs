@@ -249,6 +249,17 @@ impl UserSettings {
         )
     }
 
+    pub fn snapshot_parallelism(&self) -> Result<Option<usize>, config::ConfigError> {
+        match self.config.get::<usize>("snapshot.parallelism") {
+            Ok(0) => Err(config::ConfigError::Message(
+                "snapshot.parallelism must be greater than 0".to_owned(),
+            )),
+            Ok(n) => Ok(Some(n)),
+            Err(config::ConfigError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn max_new_file_size(&self) -> Result<u64, config::ConfigError> {
         let cfg = self
             .config
@@ -264,8 +275,13 @@ impl UserSettings {
 
     // separate from sign_settings as those two are needed in pretty different
     // places
+    /// Returns the name of the signing backend, or `None` if it's not
+    /// configured or set to `"none"`.
     pub fn signing_backend(&self) -> Option<String> {
-        self.config.get_string("signing.backend").ok()
+        self.config
+            .get_string("signing.backend")
+            .ok()
+            .filter(|backend| backend != "none")
     }
 
     pub fn sign_settings(&self) -> SignSettings {
@@ -198,6 +198,9 @@ pub struct SnapshotOptions<'a> {
     /// (depending on implementation)
     /// return `SnapshotError::NewFileTooLarge`.
     pub max_new_file_size: u64,
+    /// The number of threads to use while scanning the working copy. If
+    /// `None`, the implementation picks a default.
+    pub parallelism: Option<usize>,
 }
 
 impl SnapshotOptions<'_> {
@@ -208,6 +211,7 @@ impl SnapshotOptions<'_> {
             fsmonitor_settings: FsmonitorSettings::None,
             progress: None,
             max_new_file_size: u64::MAX,
+            parallelism: None,
         }
     }
 }
@@ -230,6 +234,8 @@ pub struct CheckoutStats {
     /// working copy but were skipped because there was an untracked (probably
     /// ignored) file in its place.
     pub skipped_files: u32,
+    /// The paths of the skipped files, in the order they were visited.
+    pub skipped_paths: Vec<RepoPathBuf>,
 }
 
 /// The working-copy checkout failed.
@@ -440,14 +440,35 @@ pub struct WorkspaceLoader {
 
 pub type WorkingCopyFactories = HashMap<String, Box<dyn WorkingCopyFactory>>;
 
-impl WorkspaceLoader {
-    pub fn init(workspace_root: &Path) -> Result<Self, WorkspaceLoadError> {
-        let jj_dir = workspace_root.join(".jj");
-        if !jj_dir.is_dir() {
+/// Finds the `.jj` directory of the workspace at `workspace_root`.
+///
+/// If `.jj` is a file, its contents are interpreted as a path to the actual
+/// directory, relative to the workspace root. This allows the directory to be
+/// relocated, similar to Git's `.git` file.
+fn resolve_jj_dir(workspace_root: &Path) -> Result<PathBuf, WorkspaceLoadError> {
+    let jj_dir = workspace_root.join(".jj");
+    if jj_dir.is_file() {
+        let buf = fs::read(&jj_dir).context(&jj_dir)?;
+        let jj_dir_str = String::from_utf8(buf).map_err(|_| WorkspaceLoadError::NonUnicodePath)?;
+        let target_dir = workspace_root.join(jj_dir_str.trim_end_matches(['\n', '\r']));
+        if !target_dir.is_dir() {
             return Err(WorkspaceLoadError::NoWorkspaceHere(
                 workspace_root.to_owned(),
             ));
         }
+        Ok(target_dir)
+    } else if jj_dir.is_dir() {
+        Ok(jj_dir)
+    } else {
+        Err(WorkspaceLoadError::NoWorkspaceHere(
+            workspace_root.to_owned(),
+        ))
+    }
+}
+
+impl WorkspaceLoader {
+    pub fn init(workspace_root: &Path) -> Result<Self, WorkspaceLoadError> {
+        let jj_dir = resolve_jj_dir(workspace_root)?;
         let mut repo_dir = jj_dir.join("repo");
         // If .jj/repo is a file, then we interpret its contents as a relative path to
         // the actual repo directory (typically in another workspace).
@@ -481,6 +481,11 @@ fn test_conflicting_changes_on_disk() {
             added_files: 3,
             removed_files: 0,
             skipped_files: 3,
+            skipped_paths: vec![
+                dir_file_path.to_owned(),
+                file_dir_path.join(RepoPathComponent::new("file")),
+                file_file_path.to_owned(),
+            ],
         }
     );
 
@@ -637,6 +642,7 @@ fn test_materialize_snapshot_conflicted_files() {
             added_files: 2,
             removed_files: 0,
             skipped_files: 0,
+            skipped_paths: vec![],
         }
     );
 
@@ -1280,3 +1286,30 @@ fn test_snapshot_max_new_file_size() {
         "the failure should be attributed to new file size"
     );
 }
+
+#[test]
+fn test_snapshot_parallelism() {
+    let settings = UserSettings::from_config(
+        testutils::base_config()
+            .add_source(config::File::from_str(
+                "snapshot.parallelism = 2",
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(settings.snapshot_parallelism().unwrap(), Some(2));
+    let mut test_workspace = TestWorkspace::init(&settings);
+    let workspace_root = test_workspace.workspace.workspace_root().clone();
+    let paths = (0..10)
+        .map(|i| RepoPathBuf::from_internal_string(format!("dir{}/file{i}", i % 3)))
+        .collect_vec();
+    for path in &paths {
+        testutils::write_working_copy_file(&workspace_root, path, "contents");
+    }
+    let tree = test_workspace.snapshot().unwrap();
+    assert_eq!(
+        tree.entries().map(|(path, _)| path).collect_vec(),
+        paths.iter().sorted().cloned().collect_vec()
+    );
+}
@@ -458,8 +458,8 @@ fn test_resolve_op_id() {
     assert_matches!(
         resolve("2"),
         Err(OpsetEvaluationError::OpsetResolution(
-            OpsetResolutionError::AmbiguousIdPrefix(_)
-        ))
+            OpsetResolutionError::AmbiguousIdPrefix { candidates, .. }
+        )) if candidates == [operations[2].id().clone(), operations[5].id().clone()]
     );
     // Empty id
     assert_matches!(
@@ -487,8 +487,12 @@ fn test_resolve_op_id() {
     assert_matches!(
         resolve("0"),
         Err(OpsetEvaluationError::OpsetResolution(
-            OpsetResolutionError::AmbiguousIdPrefix(_)
-        ))
+            OpsetResolutionError::AmbiguousIdPrefix { candidates, .. }
+        )) if candidates == [
+            operations[3].id().clone(),
+            operations[6].id().clone(),
+            root_operation.id().clone(),
+        ]
     );
 }
 
@@ -504,6 +508,38 @@ fn test_resolve_current_op() {
     );
 }
 
+#[test]
+fn test_resolve_root_op() {
+    let settings = testutils::user_settings();
+    let test_repo = TestRepo::init_with_settings(&settings);
+    let repo = test_repo.repo;
+    let root_op = repo
+        .operation()
+        .parents()
+        .exactly_one()
+        .ok()
+        .unwrap()
+        .unwrap();
+    assert!(root_op.parent_ids().is_empty());
+
+    let tx = repo.start_transaction(&settings);
+    let repo = tx.commit("test");
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo, "root").unwrap(),
+        root_op
+    );
+    assert_eq!(
+        op_walk::resolve_op_with_repo(&repo, "root++").unwrap(),
+        *repo.operation()
+    );
+    assert_matches!(
+        op_walk::resolve_op_with_repo(&repo, "root-"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::EmptyOperations(_)
+        ))
+    );
+}
+
 #[test]
 fn test_resolve_op_parents_children() {
     // Use monotonic timestamp to stabilize merge order of transactions
@@ -592,6 +628,73 @@ fn test_resolve_op_parents_children() {
     );
 }
 
+#[test]
+fn test_resolve_op_at_time() {
+    let settings_at = |timestamp: &str| {
+        UserSettings::from_config(
+            testutils::base_config()
+                .add_source(config::File::from_str(
+                    &format!("debug.operation-timestamp = '{timestamp}'"),
+                    config::FileFormat::Toml,
+                ))
+                .build()
+                .unwrap(),
+        )
+    };
+    let test_repo = TestRepo::init_with_settings(&settings_at("2001-02-02T04:05:06+07:00"));
+    let mut repo = test_repo.repo;
+    let mut operations = Vec::new();
+    for timestamp in ["2001-02-03T04:05:06+07:00", "2001-02-03T06:05:06+07:00"] {
+        let tx = repo.start_transaction(&settings_at(timestamp));
+        repo = tx.commit("test");
+        operations.push(repo.operation().clone());
+    }
+    let resolve = |op_str: &str| op_walk::resolve_op_with_repo(&repo, op_str);
+
+    // Exact and in-between times
+    assert_eq!(
+        resolve("@{2001-02-03T04:05:06+07:00}").unwrap(),
+        operations[0]
+    );
+    assert_eq!(
+        resolve("@{2001-02-03T05:00:00+07:00}").unwrap(),
+        operations[0]
+    );
+    assert_eq!(
+        resolve("@{2001-02-03T06:05:06+07:00}").unwrap(),
+        operations[1]
+    );
+    // Postfix operators apply to the resolved operation
+    assert_eq!(
+        resolve("@{2001-02-03T06:05:06+07:00}-").unwrap(),
+        operations[0]
+    );
+    // Relative times, with and without braces
+    assert_eq!(resolve("@{now}").unwrap(), operations[1]);
+    assert_eq!(resolve("@{yesterday}").unwrap(), operations[1]);
+    assert_eq!(resolve("2 hours ago").unwrap(), operations[1]);
+    // The root operation isn't a candidate
+    assert_matches!(
+        resolve("@{2001-02-01}"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::NoOperationAtTime(_)
+        ))
+    );
+    assert_matches!(
+        resolve("@{2 fortnights ago}"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::InvalidTimeExpression(_)
+        ))
+    );
+    // Bare symbol that is neither an ID nor a time
+    assert_matches!(
+        resolve("bogus"),
+        Err(OpsetEvaluationError::OpsetResolution(
+            OpsetResolutionError::InvalidIdPrefix(_)
+        ))
+    );
+}
+
 #[test]
 fn test_gc() {
     let settings = stable_op_id_settings();
@@ -240,6 +240,7 @@ impl TestWorkspace {
         let mut locked_ws = self.workspace.start_working_copy_mutation().unwrap();
         let tree_id = locked_ws.locked_wc().snapshot(SnapshotOptions {
             max_new_file_size: self.settings.max_new_file_size().unwrap(),
+            parallelism: self.settings.snapshot_parallelism().unwrap(),
             ..SnapshotOptions::empty_for_test()
         })?;
         // arbitrary operation id